@@ -0,0 +1,371 @@
+//! `#[derive(TypeStateBuilder)]`, a front end to `bpb` that reads the
+//! builder definition from an annotated struct instead of a JSON file.
+//!
+//! ```ignore
+//! #[derive(TypeStateBuilder)]
+//! #[bpb(name = "PutBlockListBuilder", inline)]
+//! struct PutBlockList<'a, T>
+//! where
+//!     T: Borrow<[u8]> + 'a,
+//! {
+//!     #[bpb(constructor, trait_get = "ClientRequired<'a>")]
+//!     client: &'a Client,
+//!     #[bpb(
+//!         required,
+//!         builder_type = "ContainerNameSet",
+//!         trait_get = "ContainerNameRequired<'a>",
+//!         trait_set = "ContainerNameSupport<'a>"
+//!     )]
+//!     container_name: &'a str,
+//!     #[bpb(trait_get = "TimeoutOption", trait_set = "TimeoutSupport")]
+//!     timeout: u64,
+//! }
+//! ```
+//!
+//! Fields are optional unless marked `required` or `constructor`. The
+//! builder is named after the struct with a `Builder` suffix unless `name`
+//! is given, and has the visibility of the struct unless `visibility` is
+//! given, on the struct, on a field or on a finalizer. With `naming` on the
+//! struct, the `builder_type`, `trait_get` and `trait_set` left out are
+//! derived from the field names, see `bpb::Naming`, except for the traits
+//! of the fields marked `traits = false`.
+//!
+//! The annotated struct only describes the builder: it is not reported as
+//! unused when the builder is used instead.
+
+extern crate bpb;
+extern crate proc_macro;
+extern crate proc_macro2;
+extern crate quote;
+extern crate syn;
+
+use bpb::{
+    ConstructorField, ExtraType, Field, Finalizer, Naming, Segment, Struct, Typestate, PRIVATE,
+};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{quote, ToTokens};
+use syn::meta::ParseNestedMeta;
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Fields, GenericParam, LitBool, LitStr, Visibility,
+};
+
+#[proc_macro_derive(TypeStateBuilder, attributes(bpb))]
+pub fn derive_type_state_builder(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+// spans of the struct members, used to point bpb diagnostics at the
+// offending field
+struct Spans {
+    constructor_fields: Vec<Span>,
+    fields: Vec<Span>,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let (stc, spans) = to_struct(input)?;
+
+    let output = match bpb::generate_unformatted(&stc) {
+        Ok(output) => output,
+        Err(bpb::Error::Invalid(diagnostics)) => {
+            let mut errors = diagnostics.iter().map(|d| {
+                let span = match d.path.0.as_slice() {
+                    [Segment::Key(k), Segment::Index(i), ..] if k == "fields" => spans.fields[*i],
+                    [Segment::Key(k), Segment::Index(i), ..] if k == "constructor_fields" => {
+                        spans.constructor_fields[*i]
+                    }
+                    _ => input.ident.span(),
+                };
+                syn::Error::new(span, &d.message)
+            });
+            let mut error = errors.next().unwrap();
+            errors.for_each(|e| error.combine(e));
+            return Err(error);
+        }
+        Err(e) => return Err(syn::Error::new(input.ident.span(), e)),
+    };
+
+    let mut output: proc_macro2::TokenStream = output.parse().map_err(|e| {
+        syn::Error::new(
+            input.ident.span(),
+            format!("bpb generated invalid code: {}", e),
+        )
+    })?;
+    output.extend(read_fields(input));
+    Ok(output)
+}
+
+// the annotated struct only describes the builder and is usually never
+// built; a method reading its fields, itself allowed to be dead, keeps
+// rustc from reporting the struct and its fields as unused
+fn read_fields(input: &DeriveInput) -> proc_macro2::TokenStream {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields = match input.data {
+        Data::Struct(ref data) => data
+            .fields
+            .iter()
+            .filter_map(|f| f.ident.as_ref())
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    quote! {
+        #[allow(dead_code)]
+        impl #impl_generics #ident #ty_generics #where_clause {
+            fn __bpb_read_fields(&self) {
+                #(let _ = &self.#fields;)*
+            }
+        }
+    }
+}
+
+fn to_struct(input: &DeriveInput) -> syn::Result<(Struct, Spans)> {
+    let data = match input.data {
+        Data::Struct(ref data) => data,
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "TypeStateBuilder can only be derived for structs",
+            ))
+        }
+    };
+
+    let named = match data.fields {
+        Fields::Named(ref named) => named,
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "TypeStateBuilder requires a struct with named fields",
+            ))
+        }
+    };
+
+    let mut stc = Struct {
+        name: format!("{}Builder", input.ident),
+        visibility: Some(match input.vis {
+            Visibility::Inherited => PRIVATE.to_owned(),
+            ref vis => vis.to_token_stream().to_string(),
+        }),
+        ..Default::default()
+    };
+
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("bpb")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                stc.name = string_value(&meta)?;
+            } else if meta.path.is_ident("derive") {
+                stc.derive = Some(string_value(&meta)?);
+            } else if meta.path.is_ident("uses") {
+                stc.uses.push(string_value(&meta)?);
+            } else if meta.path.is_ident("inline") {
+                stc.inline = Some(true);
+            } else if meta.path.is_ident("prepend_required_types") {
+                stc.prepend_required_types = Some(true);
+            } else if meta.path.is_ident("prelude_path") {
+                stc.prelude_path = Some(string_value(&meta)?);
+            } else if meta.path.is_ident("typestate") {
+                typestate(&meta, &mut stc.typestate)?;
+            } else if meta.path.is_ident("naming") {
+                stc.naming = Some(naming(&meta)?);
+            } else if meta.path.is_ident("emit_traits") {
+                stc.emit_traits = Some(true);
+            } else if meta.path.is_ident("visibility") {
+                stc.visibility = Some(string_value(&meta)?);
+            } else if meta.path.is_ident("finalizer") {
+                stc.finalizers.push(finalizer(&meta)?);
+            } else {
+                return Err(meta.error("unsupported bpb struct attribute"));
+            }
+            Ok(())
+        })?;
+    }
+
+    for param in input.generics.params.iter() {
+        stc.extra_types.push(match *param {
+            GenericParam::Lifetime(ref l) => ExtraType::Lifetime {
+                name: l.lifetime.to_string(),
+                bounds: l.bounds.iter().map(|b| b.to_string()).collect(),
+            },
+            GenericParam::Type(ref t) => ExtraType::Type {
+                name: t.ident.to_string(),
+                bounds: t
+                    .bounds
+                    .iter()
+                    .map(|b| b.to_token_stream().to_string())
+                    .collect(),
+            },
+            GenericParam::Const(ref c) => ExtraType::Const {
+                name: c.ident.to_string(),
+                const_type: c.ty.to_token_stream().to_string(),
+            },
+        });
+    }
+
+    if let Some(ref where_clause) = input.generics.where_clause {
+        for predicate in where_clause.predicates.iter() {
+            stc.extra_wheres
+                .push(predicate.to_token_stream().to_string());
+        }
+    }
+
+    let mut spans = Spans {
+        constructor_fields: Vec::new(),
+        fields: Vec::new(),
+    };
+
+    for f in named.named.iter() {
+        let name = f.ident.as_ref().unwrap().to_string();
+        let field_type = f.ty.to_token_stream().to_string();
+
+        let mut constructor = false;
+        let mut field = Field {
+            name,
+            field_type,
+            optional: Some(true),
+            ..Default::default()
+        };
+
+        for attr in f.attrs.iter().filter(|a| a.path().is_ident("bpb")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("constructor") {
+                    constructor = true;
+                } else if meta.path.is_ident("required") {
+                    field.optional = Some(false);
+                } else if meta.path.is_ident("builder_type") {
+                    field.builder_type = Some(string_value(&meta)?);
+                } else if meta.path.is_ident("initializer") {
+                    field.initializer = Some(string_value(&meta)?);
+                } else if meta.path.is_ident("default") {
+                    // `default = "expr"`, or `default` for `Default::default()`
+                    if meta.input.peek(syn::Token![=]) {
+                        field.initializer = Some(string_value(&meta)?);
+                    } else {
+                        field.default_trait = Some(true);
+                    }
+                } else if meta.path.is_ident("trait_get") {
+                    field.trait_get = Some(string_value(&meta)?);
+                } else if meta.path.is_ident("trait_set") {
+                    field.trait_set = Some(string_value(&meta)?);
+                } else if meta.path.is_ident("get_via_clone") {
+                    field.get_via_clone = Some(true);
+                } else if meta.path.is_ident("collection") {
+                    field.collection = Some(true);
+                } else if meta.path.is_ident("setter_into") {
+                    field.setter_into = Some(true);
+                } else if meta.path.is_ident("setter_as_ref") {
+                    field.setter_as_ref = Some(true);
+                } else if meta.path.is_ident("setter_impl_trait") {
+                    field.setter_impl_trait = Some(true);
+                } else if meta.path.is_ident("visibility") {
+                    field.visibility = Some(string_value(&meta)?);
+                } else if meta.path.is_ident("traits") {
+                    field.traits = Some(meta.value()?.parse::<LitBool>()?.value);
+                } else {
+                    return Err(meta.error("unsupported bpb field attribute"));
+                }
+                Ok(())
+            })?;
+        }
+
+        if constructor {
+            stc.constructor_fields.push(ConstructorField {
+                name: field.name,
+                field_type: field.field_type,
+                trait_get: field.trait_get,
+                get_via_clone: field.get_via_clone,
+                visibility: field.visibility,
+                traits: field.traits,
+            });
+            spans.constructor_fields.push(f.span());
+        } else {
+            stc.fields.push(field);
+            spans.fields.push(f.span());
+        }
+    }
+
+    Ok((stc, spans))
+}
+
+fn finalizer(meta: &ParseNestedMeta) -> syn::Result<Finalizer> {
+    let mut finalizer = Finalizer::default();
+
+    meta.parse_nested_meta(|meta| {
+        if meta.path.is_ident("name") {
+            finalizer.name = string_value(&meta)?;
+        } else if meta.path.is_ident("return_type") {
+            finalizer.return_type = Some(string_value(&meta)?);
+        } else if meta.path.is_ident("by_ref") {
+            finalizer.by_ref = Some(true);
+        } else if meta.path.is_ident("async") {
+            finalizer.is_async = Some(true);
+        } else if meta.path.is_ident("body") {
+            finalizer.body = Some(string_value(&meta)?);
+        } else if meta.path.is_ident("visibility") {
+            finalizer.visibility = Some(string_value(&meta)?);
+        } else {
+            return Err(meta.error("unsupported bpb finalizer attribute"));
+        }
+        Ok(())
+    })?;
+
+    if finalizer.name.is_empty() {
+        return Err(meta.error("a finalizer needs a name"));
+    }
+    Ok(finalizer)
+}
+
+// `typestate(set = "Set", unset = "Unset", sealed = false, enums)`
+fn typestate(meta: &ParseNestedMeta, typestate: &mut Typestate) -> syn::Result<()> {
+    meta.parse_nested_meta(|meta| {
+        if meta.path.is_ident("set") {
+            typestate.set = Some(string_value(&meta)?);
+        } else if meta.path.is_ident("unset") {
+            typestate.unset = Some(string_value(&meta)?);
+        } else if meta.path.is_ident("sealed") {
+            typestate.sealed = Some(meta.value()?.parse::<LitBool>()?.value);
+        } else if meta.path.is_ident("enums") {
+            typestate.enums = Some(true);
+        } else {
+            return Err(meta.error("unsupported bpb typestate attribute"));
+        }
+        Ok(())
+    })
+}
+
+// `naming`, or `naming(builder_type = "{}Set", trait_get_required =
+// "{}Required", trait_get_optional = "{}Option", trait_set = "{}Support",
+// traits = false)`
+fn naming(meta: &ParseNestedMeta) -> syn::Result<Naming> {
+    let mut naming = Naming::default();
+    if !meta.input.peek(syn::token::Paren) {
+        return Ok(naming);
+    }
+    meta.parse_nested_meta(|meta| {
+        if meta.path.is_ident("builder_type") {
+            naming.builder_type = Some(string_value(&meta)?);
+        } else if meta.path.is_ident("trait_get_required") {
+            naming.trait_get_required = Some(string_value(&meta)?);
+        } else if meta.path.is_ident("trait_get_optional") {
+            naming.trait_get_optional = Some(string_value(&meta)?);
+        } else if meta.path.is_ident("trait_set") {
+            naming.trait_set = Some(string_value(&meta)?);
+        } else if meta.path.is_ident("traits") {
+            naming.traits = Some(meta.value()?.parse::<LitBool>()?.value);
+        } else {
+            return Err(meta.error("unsupported bpb naming attribute"));
+        }
+        Ok(())
+    })?;
+    Ok(naming)
+}
+
+fn string_value(meta: &ParseNestedMeta) -> syn::Result<String> {
+    Ok(meta.value()?.parse::<LitStr>()?.value())
+}
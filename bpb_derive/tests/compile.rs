@@ -0,0 +1,13 @@
+//! Derives builders from annotated structs, using them the way the builders
+//! generated from definition files are used in the `bpb` compile tests,
+//! and checks that mistakes in the attributes are reported on the field
+//! they are written on.
+
+extern crate trybuild;
+
+#[test]
+fn derived_builders() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass/*.rs");
+    t.compile_fail("tests/ui/fail/*.rs");
+}
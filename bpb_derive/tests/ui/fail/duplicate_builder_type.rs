@@ -0,0 +1,10 @@
+// the error points at the field repeating the builder type
+#[derive(bpb_derive::TypeStateBuilder)]
+struct GetBlob<'a> {
+    #[bpb(required, builder_type = "NameSet")]
+    container_name: &'a str,
+    #[bpb(required, builder_type = "NameSet")]
+    blob_name: &'a str,
+}
+
+fn main() {}
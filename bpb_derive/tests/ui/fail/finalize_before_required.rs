@@ -0,0 +1,14 @@
+#[path = "../../../../tests/stubs/prelude.rs"]
+mod stubs;
+use stubs::*;
+
+#[derive(bpb_derive::TypeStateBuilder)]
+#[bpb(finalizer(name = "finalize", return_type = "u64", body = "self.timeout.unwrap_or(0)"))]
+struct GetBlob {
+    #[bpb(required, builder_type = "TimeoutSet")]
+    timeout: u64,
+}
+
+fn main() {
+    GetBlobBuilder::new().finalize();
+}
@@ -0,0 +1,7 @@
+#[derive(bpb_derive::TypeStateBuilder)]
+struct GetBlob {
+    #[bpb(optinal)]
+    timeout: u64,
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+// `default`, `default = "expr"`, `constructor` and finalizers with a body
+#![deny(dead_code)]
+
+#[path = "../../../../tests/stubs/prelude.rs"]
+mod stubs;
+use stubs::*;
+
+#[derive(bpb_derive::TypeStateBuilder)]
+#[bpb(finalizer(name = "total", return_type = "u64", by_ref, body = "self.base + self.extra"))]
+struct Sum {
+    #[bpb(constructor)]
+    base: u64,
+    #[bpb(required, builder_type = "ExtraSet", default = "1")]
+    extra: u64,
+    #[bpb(default, get_via_clone)]
+    labels: Vec<String>,
+}
+
+fn main() {
+    let builder = SumBuilder::new(40).with_extra(2);
+    assert_eq!(builder.base(), 40);
+    assert_eq!(builder.labels(), Vec::<String>::new());
+    assert_eq!(builder.total(), 42);
+}
@@ -0,0 +1,28 @@
+// `naming` with `emit_traits`, and fields opting out of the traits
+#![deny(dead_code)]
+
+#[path = "../../../../tests/stubs/prelude.rs"]
+mod stubs;
+use stubs::{Client, No, PhantomData, ToAssign, Yes};
+
+#[derive(bpb_derive::TypeStateBuilder)]
+#[bpb(naming, emit_traits)]
+struct GetBlob<'a> {
+    #[bpb(constructor, traits = false)]
+    client: &'a Client,
+    #[bpb(required)]
+    container_name: &'a str,
+    #[bpb(traits = false)]
+    timeout: u64,
+}
+
+fn main() {
+    let client = Client;
+
+    let builder = GetBlobBuilder::new(&client)
+        .with_container_name("container")
+        .with_timeout(30);
+    let _: &Client = builder.client();
+    assert_eq!(ContainerNameRequired::container_name(&builder), "container");
+    assert_eq!(builder.timeout(), Some(30));
+}
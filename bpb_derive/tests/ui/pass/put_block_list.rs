@@ -0,0 +1,65 @@
+// the builder of put_block_list_builder.json, used like its snapshot in
+// tests/ui/pass/put_block_list_builder.rs; the annotated struct is never
+// built, which must not be reported
+#![deny(dead_code)]
+
+#[path = "../../../../tests/stubs/prelude.rs"]
+mod stubs;
+use stubs::*;
+
+#[derive(bpb_derive::TypeStateBuilder)]
+#[bpb(
+    name = "PutBlockListBuilder",
+    inline,
+    finalizer(
+        name = "finalize",
+        return_type = "impl Future<Item = PutBlockListResponse, Error = AzureError>"
+    )
+)]
+struct PutBlockList<'a, T>
+where
+    T: Borrow<[u8]> + 'a,
+{
+    #[bpb(constructor, trait_get = "ClientRequired<'a>")]
+    client: &'a Client,
+    #[bpb(
+        required,
+        builder_type = "ContainerNameSet",
+        trait_get = "ContainerNameRequired<'a>",
+        trait_set = "ContainerNameSupport<'a>"
+    )]
+    container_name: &'a str,
+    #[bpb(
+        required,
+        builder_type = "BlockListSet",
+        trait_get = "BlockListRequired<'a, T>",
+        trait_set = "BlockListSupport<'a, T>"
+    )]
+    block_list: &'a BlockList<T>,
+    #[bpb(trait_get = "TimeoutOption", trait_set = "TimeoutSupport")]
+    timeout: u64,
+    #[bpb(
+        trait_get = "ClientRequestIdOption<'a>",
+        trait_set = "ClientRequestIdSupport<'a>"
+    )]
+    client_request_id: &'a str,
+}
+
+fn main() {
+    let client = Client;
+    let block_list = BlockList {
+        blocks: vec![vec![0u8]],
+    };
+
+    let builder = PutBlockListBuilder::new(&client)
+        .with_timeout(30)
+        .with_container_name("container")
+        .with_block_list(&block_list)
+        .with_client_request_id("id");
+
+    assert_eq!(builder.container_name(), "container");
+    assert_eq!(builder.timeout(), Some(30));
+    assert_eq!(builder.client_request_id(), Some("id"));
+
+    let _finalize = |builder: PutBlockListBuilder<Vec<u8>, Yes, Yes>| builder.finalize();
+}
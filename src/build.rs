@@ -0,0 +1,130 @@
+//! Helpers for generating builders from a `build.rs` script.
+//!
+//! ```no_run
+//! // build.rs
+//! extern crate bpb;
+//!
+//! fn main() {
+//!     let out_dir = std::env::var("OUT_DIR").unwrap();
+//!     bpb::build::generate_dir("builders", out_dir).unwrap();
+//! }
+//! ```
+//!
+//! The generated files can then be pulled in with
+//! `include!(concat!(env!("OUT_DIR"), "/put_block_list_builder.rs"));`.
+//! A catalog or a typestate configuration kept with the definitions is
+//! given with [`generate_dir_with`].
+
+use error::Error;
+use generate::generate;
+use load::{load_all, load_all_with_catalog, load_catalog, load_typestate};
+use model::Typestate;
+use naming::snake_case;
+use output::{render, write_file};
+use std::fs;
+use std::path::{Path, PathBuf};
+use syntax::Syntax;
+
+/// The files shared by the definitions of a directory. Either of them may
+/// be kept in the directory itself, it is then not read as a definition.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    /// The catalog the `use` references of the fields are resolved
+    /// against.
+    pub catalog: Option<PathBuf>,
+    /// The typestate configuration used for the settings a builder's own
+    /// `typestate` leaves out.
+    pub typestate: Option<PathBuf>,
+}
+
+/// Generates one `.rs` file in `out_dir` for every builder defined in the
+/// definition files found in `input_dir`, that is the files with an
+/// extension known to [`Syntax::from_path`], named after the builder in
+/// snake case. Prints the `cargo:rerun-if-changed` lines for the directory
+/// and each definition.
+///
+/// Nothing is written if two builders would go to the same file, which is
+/// returned as `Error::Clash`. Returns the paths of the generated files.
+pub fn generate_dir<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_dir: P,
+    out_dir: Q,
+) -> Result<Vec<PathBuf>, Error> {
+    generate_dir_with(input_dir, out_dir, &Config::default())
+}
+
+/// Like [`generate_dir`], with the catalog and typestate configuration of
+/// `config`.
+pub fn generate_dir_with<P: AsRef<Path>, Q: AsRef<Path>>(
+    input_dir: P,
+    out_dir: Q,
+    config: &Config,
+) -> Result<Vec<PathBuf>, Error> {
+    let input_dir = input_dir.as_ref();
+    let out_dir = out_dir.as_ref();
+
+    println!("cargo:rerun-if-changed={}", input_dir.display());
+
+    let catalog = match config.catalog {
+        Some(ref path) => {
+            println!("cargo:rerun-if-changed={}", path.display());
+            Some(load_catalog(path).map_err(|e| e.in_file(path.as_path()))?)
+        }
+        None => None,
+    };
+    let typestate = match config.typestate {
+        Some(ref path) => {
+            println!("cargo:rerun-if-changed={}", path.display());
+            load_typestate(path).map_err(|e| e.in_file(path.as_path()))?
+        }
+        None => Typestate::default(),
+    };
+
+    let shared = [&config.catalog, &config.typestate]
+        .iter()
+        .filter_map(|path| path.as_ref().and_then(|p| fs::canonicalize(p).ok()))
+        .collect::<Vec<_>>();
+    let mut inputs = Vec::new();
+    for entry in fs::read_dir(input_dir).map_err(|e| Error::from(e).in_file(input_dir))? {
+        let path = entry.map_err(|e| Error::from(e).in_file(input_dir))?.path();
+        if path.is_file()
+            && Syntax::from_path(&path).is_some()
+            && !fs::canonicalize(&path).is_ok_and(|p| shared.contains(&p))
+        {
+            inputs.push(path);
+        }
+    }
+    inputs.sort();
+
+    // the file, the builder and its definition, and the contents of every
+    // output
+    let mut outputs: Vec<(PathBuf, String, String)> = Vec::new();
+    for input in inputs.iter() {
+        println!("cargo:rerun-if-changed={}", input.display());
+
+        let structs = match catalog {
+            Some(ref catalog) => load_all_with_catalog(input, catalog),
+            None => load_all(input),
+        };
+        for mut stc in structs.map_err(|e| e.in_file(input.as_path()))? {
+            stc.typestate = stc.typestate.or(&typestate);
+            let output = generate(&stc).map_err(|e| e.in_file(input.as_path()))?;
+
+            let out_file = out_dir.join(format!("{}.rs", snake_case(&stc.name)));
+            let source = format!("`{}` from {}", stc.name, input.display());
+            if let Some((_, first, _)) = outputs.iter().find(|o| o.0 == out_file) {
+                return Err(Error::Clash(out_file, first.clone(), source));
+            }
+            outputs.push((out_file, source, render(&[output])));
+        }
+    }
+
+    fs::create_dir_all(out_dir).map_err(|e| Error::from(e).in_file(out_dir))?;
+
+    let mut generated = Vec::new();
+    for (out_file, _, contents) in outputs {
+        // OUT_DIR belongs to cargo, there is nothing hand written to protect
+        write_file(&out_file, &contents, true)?;
+        generated.push(out_file);
+    }
+    Ok(generated)
+}
@@ -0,0 +1,163 @@
+use error::Diagnostic;
+use locate::Path;
+use model::{Catalog, Struct};
+use traits::{field_traits, split_trait_name};
+
+/// Resolves the `use` references of the fields of `stc` against `catalog`.
+///
+/// A field taking its definition from an entry may leave out any key, which
+/// is then copied from the entry; the keys both of them set must agree,
+/// except for `builder_type`, which only names the typestate of this
+/// builder. Fields defining a catalog trait differently from the catalog
+/// are reported as well. If the catalog has a `module`, the catalog traits
+/// used by `stc` are imported from it.
+///
+/// Every problem is collected; an empty vector means `stc` was resolved.
+pub fn resolve(stc: &mut Struct, catalog: &Catalog) -> Vec<Diagnostic> {
+    let root = Path::default();
+    let mut diagnostics = Vec::new();
+
+    for (i, f) in stc.fields.iter_mut().enumerate() {
+        let path = root.key("fields").index(i);
+        let id = match f.catalog_entry.take() {
+            Some(id) => id,
+            None => continue,
+        };
+        let entry = match catalog.fields.get(&id) {
+            Some(entry) => entry,
+            None => {
+                diagnostics.push(Diagnostic::new(
+                    path.key("use"),
+                    format!("unknown catalog entry `{}`", id),
+                ));
+                continue;
+            }
+        };
+
+        let mut conflict = |key: &str, value: &str| {
+            diagnostics.push(Diagnostic::new(
+                path.key(key),
+                format!(
+                    "conflicts with catalog entry `{}`, which sets `{}` to `{}`",
+                    id, key, value
+                ),
+            ))
+        };
+
+        merge(&mut f.name, &entry.name, |v| conflict("name", v));
+        merge(&mut f.field_type, &entry.field_type, |v| {
+            conflict("field_type", v)
+        });
+        merge_flag(&mut f.optional, entry.optional, |v| conflict("optional", v));
+        if f.builder_type.is_none() {
+            f.builder_type = entry.builder_type.clone();
+        }
+        merge_option(&mut f.initializer, &entry.initializer, |v| {
+            conflict("initializer", v)
+        });
+        merge_option(&mut f.trait_get, &entry.trait_get, |v| {
+            conflict("trait_get", v)
+        });
+        merge_option(&mut f.trait_set, &entry.trait_set, |v| {
+            conflict("trait_set", v)
+        });
+        merge_flag(&mut f.default_trait, entry.default_trait, |v| {
+            conflict("default_trait", v)
+        });
+        merge_flag(&mut f.get_via_clone, entry.get_via_clone, |v| {
+            conflict("get_via_clone", v)
+        });
+        merge_flag(&mut f.setter_into, entry.setter_into, |v| {
+            conflict("setter_into", v)
+        });
+        merge_flag(&mut f.setter_as_ref, entry.setter_as_ref, |v| {
+            conflict("setter_as_ref", v)
+        });
+        merge_flag(&mut f.setter_impl_trait, entry.setter_impl_trait, |v| {
+            conflict("setter_impl_trait", v)
+        });
+        merge_flag(&mut f.collection, entry.collection, |v| {
+            conflict("collection", v)
+        });
+    }
+
+    if !diagnostics.is_empty() {
+        return diagnostics;
+    }
+
+    // the traits of the catalog: name, id of the defining entry, generic
+    // parameters and items
+    let mut catalog_traits = Vec::new();
+    for (id, entry) in catalog.fields.iter() {
+        for (_, trait_name, items) in field_traits(&entry.to_field()) {
+            if let Some((name, params)) = split_trait_name(trait_name) {
+                catalog_traits.push((name, id, params, items));
+            }
+        }
+    }
+
+    let mut imports = Vec::new();
+    for (i, f) in stc.fields.iter().enumerate() {
+        let path = root.key("fields").index(i);
+        for (key, trait_name, items) in field_traits(f) {
+            let (name, params) = match split_trait_name(trait_name) {
+                Some(split) => split,
+                None => continue,
+            };
+            match catalog_traits.iter().find(|t| t.0 == name) {
+                Some(t) if t.2 != params || t.3 != items => diagnostics.push(Diagnostic::new(
+                    path.key(key),
+                    format!(
+                        "trait `{}` is defined differently by catalog entry `{}`",
+                        name, t.1
+                    ),
+                )),
+                Some(_) => {
+                    if let Some(ref module) = catalog.module {
+                        let import = format!("{}::{}", module, name);
+                        if !imports.contains(&import) {
+                            imports.push(import);
+                        }
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+
+    for import in imports {
+        if !stc.uses.contains(&import) {
+            stc.uses.push(import);
+        }
+    }
+
+    diagnostics
+}
+
+fn merge<F: FnMut(&str)>(mine: &mut String, theirs: &str, mut conflict: F) {
+    if mine.is_empty() {
+        *mine = theirs.to_owned();
+    } else if mine != theirs {
+        conflict(theirs);
+    }
+}
+
+fn merge_option<F: FnMut(&str)>(
+    mine: &mut Option<String>,
+    theirs: &Option<String>,
+    mut conflict: F,
+) {
+    match (mine.as_ref(), theirs.as_ref()) {
+        (Some(m), Some(t)) if m != t => conflict(t),
+        (None, _) => *mine = theirs.clone(),
+        _ => {}
+    }
+}
+
+fn merge_flag<F: FnMut(&str)>(mine: &mut Option<bool>, theirs: Option<bool>, mut conflict: F) {
+    match (*mine, theirs) {
+        (Some(m), Some(t)) if m != t => conflict(&t.to_string()),
+        (None, _) => *mine = theirs,
+        _ => {}
+    }
+}
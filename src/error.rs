@@ -0,0 +1,126 @@
+use locate::{Location, Path};
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// A single problem found in a definition.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub path: Path,
+    pub message: String,
+    pub location: Option<Location>,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Parse(serde_json::Error),
+    Invalid(Vec<Diagnostic>),
+    /// An error that happened while processing the given file.
+    InFile(PathBuf, Box<Error>),
+    /// The file exists but was not generated by bpb, so it was not
+    /// overwritten.
+    NotGenerated(PathBuf),
+    /// Two outputs, described by the strings, would be written to the
+    /// same file.
+    Clash(PathBuf, String, String),
+    /// The generated code is not valid Rust, usually because of a mistake
+    /// in a type or expression of the definition.
+    Syntax(String),
+}
+
+impl Diagnostic {
+    pub fn new(path: Path, message: String) -> Diagnostic {
+        Diagnostic {
+            path,
+            message,
+            location: None,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(location) = self.location {
+            write!(f, "{}: ", location)?;
+        }
+        // errors of the parsers only have a location
+        if !self.path.0.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref e) => write!(f, "{}", e),
+            Error::Parse(ref e) => write!(f, "{}", e),
+            Error::Invalid(ref diagnostics) => {
+                for (i, d) in diagnostics.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}", d)?;
+                }
+                Ok(())
+            }
+            Error::InFile(ref path, ref e) => match **e {
+                Error::Invalid(ref diagnostics) => {
+                    for (i, d) in diagnostics.iter().enumerate() {
+                        if i > 0 {
+                            writeln!(f)?;
+                        }
+                        if d.location.is_some() {
+                            write!(f, "{}:{}", path.display(), d)?;
+                        } else {
+                            write!(f, "{}: {}", path.display(), d)?;
+                        }
+                    }
+                    Ok(())
+                }
+                _ => write!(f, "{}: {}", path.display(), e),
+            },
+            Error::NotGenerated(ref path) => write!(
+                f,
+                "{}: not generated by bpb, refusing to overwrite it",
+                path.display()
+            ),
+            Error::Clash(ref path, ref first, ref second) => write!(
+                f,
+                "{}: both {} and {} would be written to it",
+                path.display(),
+                first,
+                second
+            ),
+            Error::Syntax(ref message) => {
+                write!(f, "the generated code does not parse: {}", message)
+            }
+        }
+    }
+}
+
+impl Error {
+    /// Attaches the file being processed to the error.
+    pub fn in_file<P: Into<PathBuf>>(self, path: P) -> Error {
+        match self {
+            Error::InFile(..) => self,
+            e => Error::InFile(path.into(), Box::new(e)),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Error {
+        Error::Parse(e)
+    }
+}
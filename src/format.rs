@@ -0,0 +1,1516 @@
+use error::Error;
+use std::cmp::Ordering;
+use syn::punctuated::Punctuated;
+use syn::token::Comma;
+use syn::{
+    Attribute, Block, Expr, Fields, FnArg, Generics, ImplItem, Item, ItemImpl, ItemStruct,
+    ItemTrait, Macro, MacroDelimiter, Path, PathArguments, ReturnType, Signature, Stmt, TraitItem,
+    Type, TypeParamBound, UseTree, Visibility, WherePredicate,
+};
+
+/// The line width rustfmt formats to by default.
+const MAX_WIDTH: usize = 100;
+/// rustfmt's `fn_call_width`: arguments wider than this are laid out
+/// vertically.
+const FN_CALL_WIDTH: usize = 60;
+/// rustfmt's `short_array_element_width_threshold`.
+const SHORT_ITEM_WIDTH: usize = 10;
+/// The macros rustfmt keeps the leading arguments of on one line, with the
+/// number of arguments before the format string.
+const SPECIAL_MACROS: &[(&str, usize)] = &[
+    ("eprint!", 0),
+    ("eprintln!", 0),
+    ("format!", 0),
+    ("format_args!", 0),
+    ("print!", 0),
+    ("println!", 0),
+    ("panic!", 0),
+    ("unreachable!", 0),
+    ("assert!", 1),
+    ("debug_assert!", 1),
+    ("write!", 1),
+    ("writeln!", 1),
+    ("assert_eq!", 2),
+    ("assert_ne!", 2),
+    ("debug_assert_eq!", 2),
+    ("debug_assert_ne!", 2),
+];
+
+/// Formats generated code the way rustfmt does, without running it.
+///
+/// Formatting happens in-process so the output only depends on the input,
+/// never on the tools installed on the machine. Types, expressions and the
+/// items bpb does not lay out itself are printed with `prettyplease`; impl
+/// and trait headers, `where` clauses, function signatures, and call,
+/// macro and array arguments, where `prettyplease` and rustfmt disagree,
+/// follow rustfmt's rules. `prettyplease` drops comments: to keep the comments the generator
+/// writes between items, the code is split at every line starting with
+/// `//` and each piece is printed on its own.
+pub fn format(code: &str) -> Result<String, Error> {
+    let mut s = String::new();
+    let mut section = String::new();
+    // comments stick to the items that follow them, everything else is
+    // separated by a blank line
+    let mut after_comment = false;
+
+    for line in code.lines() {
+        if !line.starts_with("//") {
+            section.push_str(line);
+            section.push('\n');
+            continue;
+        }
+
+        if !section.trim().is_empty() {
+            if !s.is_empty() && !after_comment {
+                s.push('\n');
+            }
+            s.push_str(&format_section(&section)?);
+            after_comment = false;
+        }
+        section.clear();
+
+        if !s.is_empty() && !after_comment {
+            s.push('\n');
+        }
+        s.push_str(line.trim_end());
+        s.push('\n');
+        after_comment = true;
+    }
+
+    if !section.trim().is_empty() {
+        if !s.is_empty() && !after_comment {
+            s.push('\n');
+        }
+        s.push_str(&format_section(&section)?);
+    }
+
+    Ok(s)
+}
+
+// every item is separated by a blank line, except consecutive `use` items
+// which are kept together and sorted
+fn format_section(section: &str) -> Result<String, Error> {
+    let file = syn::parse_file(section).map_err(|e| Error::Syntax(e.to_string()))?;
+
+    let mut s = String::new();
+    let mut uses = Vec::new();
+    for item in file.items {
+        if let Item::Use(_) = item {
+            uses.push(format_item(item, 0));
+            continue;
+        }
+        push_uses(&mut s, &mut uses);
+        if !s.is_empty() {
+            s.push('\n');
+        }
+        s.push_str(&format_item(item, 0));
+    }
+    push_uses(&mut s, &mut uses);
+    Ok(s)
+}
+
+fn push_uses(s: &mut String, uses: &mut Vec<String>) {
+    if uses.is_empty() {
+        return;
+    }
+    if !s.is_empty() {
+        s.push('\n');
+    }
+    uses.sort_by(|a, b| compare_uses(a, b));
+    for u in uses.drain(..) {
+        s.push_str(&u);
+    }
+}
+
+/// Orders two `use` items, or two paths, the way rustfmt sorts them:
+/// segment by segment, `self`, `super` and `crate` first, then names, with
+/// snake_case before CamelCase before UPPER_CASE, then globs and lists.
+pub fn compare_uses(a: &str, b: &str) -> Ordering {
+    let a = use_segments(use_path(a));
+    let b = use_segments(use_path(b));
+    for (a, b) in a.iter().zip(b.iter()) {
+        let ordering = compare_segments(a, b);
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+// the path of a `use` item, or `item` itself if it is a path
+fn use_path(item: &str) -> &str {
+    let item = item.trim();
+    let path = match item.find("use ") {
+        Some(start) if item.ends_with(';') => &item[start + "use ".len()..item.len() - 1],
+        _ => item,
+    };
+    path.trim().trim_start_matches("::")
+}
+
+// the segments of `path`, the last of which may be a `{…}` list
+fn use_segments(path: &str) -> Vec<&str> {
+    match path.find('{') {
+        Some(brace) => {
+            let mut segments: Vec<&str> = path[..brace]
+                .split("::")
+                .filter(|s| !s.is_empty())
+                .collect();
+            segments.push(&path[brace..]);
+            segments
+        }
+        None => path.split("::").map(str::trim).collect(),
+    }
+}
+
+fn compare_segments(a: &str, b: &str) -> Ordering {
+    fn rank(segment: &str) -> u8 {
+        match segment {
+            "self" => 0,
+            "super" => 1,
+            "crate" => 2,
+            "*" => 4,
+            _ if segment.starts_with('{') => 5,
+            _ => 3,
+        }
+    }
+    match (rank(a), rank(b)) {
+        (3, 3) => compare_names(a, b),
+        (5, 5) => {
+            let a = list_items(a);
+            let b = list_items(b);
+            for (a, b) in a.iter().zip(b.iter()) {
+                let ordering = compare_uses(a, b);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            a.len().cmp(&b.len())
+        }
+        (a, b) => a.cmp(&b),
+    }
+}
+
+fn compare_names(a: &str, b: &str) -> Ordering {
+    fn case(name: &str) -> u8 {
+        if name
+            .chars()
+            .all(|c| c.is_uppercase() || c.is_numeric() || c == '_')
+        {
+            2
+        } else if name.starts_with(char::is_uppercase) {
+            1
+        } else {
+            0
+        }
+    }
+    case(a).cmp(&case(b)).then_with(|| a.cmp(b))
+}
+
+// the items of a `{…}` list
+fn list_items(list: &str) -> Vec<&str> {
+    let inner = list.trim_start_matches('{').trim_end_matches('}');
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in inner.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(inner[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(inner[start..].trim());
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+// rustfmt sorts the items of every `{…}` list like the `use` items, and
+// replaces a list of a single item other than `self` with the item
+fn sort_use_tree(tree: &mut UseTree) {
+    match *tree {
+        UseTree::Path(ref mut path) => sort_use_tree(&mut path.tree),
+        UseTree::Group(ref mut group) => {
+            let mut items: Vec<UseTree> = group.items.iter().cloned().collect();
+            for item in items.iter_mut() {
+                sort_use_tree(item);
+            }
+            items.sort_by(|a, b| compare_uses(&print_use_tree(a), &print_use_tree(b)));
+            group.items = items.into_iter().collect();
+        }
+        UseTree::Name(_) | UseTree::Rename(_) | UseTree::Glob(_) => {}
+    }
+
+    let single = match *tree {
+        UseTree::Group(ref group) if group.items.len() == 1 => match group.items[0] {
+            UseTree::Name(ref name) if name.ident == "self" => None,
+            ref item => Some(item.clone()),
+        },
+        _ => None,
+    };
+    if let Some(item) = single {
+        *tree = item;
+    }
+}
+
+// rustfmt's `rewrite_nested_use_tree`: a list holding another list has one
+// item per line, other lists stay on one line if they fit, otherwise
+// their items are packed on as few lines as possible
+fn format_use(item: &syn::ItemUse, indent: usize) -> String {
+    let mut s = print_attributes(&item.attrs, indent);
+    let mut head = format!("{}{}use ", " ".repeat(indent), print_visibility(&item.vis));
+    if item.leading_colon.is_some() {
+        head.push_str("::");
+    }
+    let tree = print_use_tree_wrapped(&item.tree, indent, head.len(), ";".len());
+    s.push_str(&format!("{}{};\n", head, tree));
+    s
+}
+
+// `tree`, starting `used` columns into a line at `indent` and followed by
+// `tail` columns
+fn print_use_tree_wrapped(tree: &UseTree, indent: usize, used: usize, tail: usize) -> String {
+    let group = match *tree {
+        UseTree::Path(ref path) => {
+            let head = format!("{}::", path.ident);
+            let rest = print_use_tree_wrapped(&path.tree, indent, used + head.len(), tail);
+            return format!("{}{}", head, rest);
+        }
+        UseTree::Group(ref group) => group,
+        _ => return print_use_tree(tree),
+    };
+
+    let flat = print_use_tree(tree);
+    let nested = group.items.iter().any(has_use_list);
+    if !nested && used + flat.len() + tail <= MAX_WIDTH {
+        return flat;
+    }
+
+    let pad = " ".repeat(indent + 4);
+    let mut s = String::from("{\n");
+    if nested {
+        for item in group.items.iter() {
+            let item = print_use_tree_wrapped(item, indent + 4, pad.len(), ",".len());
+            s.push_str(&format!("{}{},\n", pad, item));
+        }
+    } else {
+        let mut line = String::new();
+        for item in group.items.iter() {
+            let item = format!("{},", print_use_tree(item));
+            if !line.is_empty() && pad.len() + line.len() + " ".len() + item.len() > MAX_WIDTH {
+                s.push_str(&format!("{}{}\n", pad, line));
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&item);
+        }
+        s.push_str(&format!("{}{}\n", pad, line));
+    }
+    s.push_str(&" ".repeat(indent));
+    s.push('}');
+    s
+}
+
+fn has_use_list(tree: &UseTree) -> bool {
+    match *tree {
+        UseTree::Path(ref path) => has_use_list(&path.tree),
+        UseTree::Group(_) => true,
+        UseTree::Name(_) | UseTree::Rename(_) | UseTree::Glob(_) => false,
+    }
+}
+
+fn print_use_tree(tree: &UseTree) -> String {
+    match *tree {
+        UseTree::Path(ref path) => format!("{}::{}", path.ident, print_use_tree(&path.tree)),
+        UseTree::Name(ref name) => name.ident.to_string(),
+        UseTree::Rename(ref rename) => format!("{} as {}", rename.ident, rename.rename),
+        UseTree::Glob(_) => "*".to_owned(),
+        UseTree::Group(ref group) => {
+            let items: Vec<String> = group.items.iter().map(print_use_tree).collect();
+            format!("{{{}}}", items.join(", "))
+        }
+    }
+}
+
+fn format_item(item: Item, indent: usize) -> String {
+    match item {
+        Item::Impl(item) => format_impl(&item, indent),
+        Item::Trait(item) => format_trait(&item, indent),
+        Item::Struct(item) => format_struct(&item, indent),
+        Item::Fn(item) => format_fn(&item.attrs, &item.vis, &item.sig, Some(&item.block), indent),
+        Item::Use(mut item) => {
+            sort_use_tree(&mut item.tree);
+            format_use(&item, indent)
+        }
+        item => indented(&unparse(item), indent),
+    }
+}
+
+// rustfmt's `format_struct_struct`: the generic parameters that do not fit
+// go one per line at the indentation of the fields, and `where` follows the
+// closing `>`
+fn format_struct(item: &ItemStruct, indent: usize) -> String {
+    match item.fields {
+        Fields::Named(ref fields) if !fields.named.is_empty() => {}
+        _ => return indented(&unparse(Item::Struct(item.clone())), indent),
+    }
+
+    let pad = " ".repeat(indent);
+    let mut result = print_attributes(&item.attrs, indent);
+    let mut head = format!(
+        "{}{}struct {}",
+        pad,
+        print_visibility(&item.vis),
+        item.ident
+    );
+    let budget = MAX_WIDTH - head.len() - "<>".len();
+    head.push_str(&print_generics_wrapped(
+        &item.generics,
+        indent + 4,
+        indent,
+        budget,
+    ));
+
+    let predicates = where_predicates(&item.generics);
+    let after_bracket = last_line_width(&head) == indent + 1;
+    let where_clause = print_where(&predicates, indent, after_bracket, None);
+    result.push_str(&head);
+    result.push_str(&where_clause);
+    if where_clause.is_empty() {
+        result.push_str(" {\n");
+    } else {
+        result.push_str(&format!("\n{}{{\n", pad));
+    }
+
+    for field in item.fields.iter() {
+        result.push_str(&format_field(field, indent + 4));
+    }
+    result.push_str(&pad);
+    result.push_str("}\n");
+    result
+}
+
+// a type that does not fit after the name of its field goes on the next
+// line, or is broken if it does not fit there either
+fn format_field(field: &syn::Field, indent: usize) -> String {
+    let mut result = print_attributes(&field.attrs, indent);
+    let head = match field.ident {
+        Some(ref ident) => format!(
+            "{}{}{}:",
+            " ".repeat(indent),
+            print_visibility(&field.vis),
+            ident
+        ),
+        None => unreachable!("only named fields are formatted"),
+    };
+    let ty = print_type(&field.ty);
+
+    if head.len() + 1 + ty.len() + ",".len() <= MAX_WIDTH {
+        result.push_str(&format!("{} {},\n", head, ty));
+    } else if indent + 4 + ty.len() + ",".len() <= MAX_WIDTH {
+        result.push_str(&format!("{}\n{}    {},\n", head, " ".repeat(indent), ty));
+    } else {
+        let ty = print_type_wrapped(&field.ty, indent, head.len() + 1, 1);
+        result.push_str(&format!("{} {},\n", head, ty));
+    }
+    result
+}
+
+// rustfmt's `format_impl`: the self type goes on its own line, after `for`
+// if there is a trait, when it does not fit; an empty impl with a single
+// bound keeps `where` on the header line
+fn format_impl(item: &ItemImpl, indent: usize) -> String {
+    let pad = " ".repeat(indent);
+    let predicates = where_predicates(&item.generics);
+
+    let mut head = pad.clone();
+    if item.unsafety.is_some() {
+        head.push_str("unsafe ");
+    }
+    head.push_str("impl");
+    // the parameters that do not fit on one line are indented twice
+    let budget = MAX_WIDTH - (indent + 4) - 1 - "impl<>".len();
+    head.push_str(&print_generics_wrapped(
+        &item.generics,
+        indent + 8,
+        indent + 4,
+        budget,
+    ));
+
+    // the brace follows the self type if there is no `where`
+    let brace = if predicates.is_empty() { 2 } else { 0 };
+    let self_ty = print_type(&item.self_ty);
+    match item.trait_ {
+        Some((ref bang, ref path, _)) => {
+            let mut trait_ref = print_path(path);
+            if bang.is_some() {
+                trait_ref.insert(0, '!');
+            }
+            // the trait goes on its own line if it does not fit after the
+            // parameters
+            if last_line_width(&head) + 1 + trait_ref.len() <= MAX_WIDTH {
+                head.push(' ');
+            } else {
+                head.push_str(&format!("\n{}    ", pad));
+            }
+            head.push_str(&trait_ref);
+            if last_line_width(&head) + " for".len() + brace + 1 + self_ty.len() <= MAX_WIDTH {
+                head.push_str(" for ");
+                head.push_str(&self_ty);
+            } else {
+                head.push_str(&format!("\n{}    for ", pad));
+                let used = last_line_width(&head);
+                head.push_str(&print_type_wrapped(&item.self_ty, indent + 4, used, 0));
+            }
+        }
+        None => {
+            if last_line_width(&head) + brace + 1 + self_ty.len() <= MAX_WIDTH {
+                head.push(' ');
+                head.push_str(&self_ty);
+            } else {
+                head.push_str(&format!("\n{}    ", pad));
+                let used = last_line_width(&head);
+                head.push_str(&print_type_wrapped(&item.self_ty, indent + 4, used, 0));
+            }
+        }
+    }
+
+    let multiline = head.contains('\n');
+    let single_line = if item.items.is_empty() && predicates.len() == 1 && !multiline {
+        Some(MAX_WIDTH - head.len())
+    } else {
+        None
+    };
+    let where_clause = print_where(&predicates, indent, false, single_line);
+
+    let mut result = print_attributes(&item.attrs, indent);
+    result.push_str(&head);
+    result.push_str(&where_clause);
+
+    if item.items.is_empty() && !multiline && head.len() + where_clause.len() <= MAX_WIDTH {
+        if where_clause.contains('\n') {
+            result.push_str(&format!("\n{}{{\n{}}}\n", pad, pad));
+        } else {
+            result.push_str(" {}\n");
+        }
+        return result;
+    }
+
+    let newline = multiline || !where_clause.is_empty();
+    if newline {
+        result.push_str(&format!("\n{}{{", pad));
+    } else {
+        result.push_str(" {");
+    }
+
+    if item.items.is_empty() {
+        if newline {
+            result.push_str(&format!("\n{}", pad));
+        }
+        result.push_str("}\n");
+        return result;
+    }
+
+    result.push('\n');
+    for impl_item in item.items.iter() {
+        result.push_str(&format_impl_item(impl_item, indent + 4));
+    }
+    result.push_str(&pad);
+    result.push_str("}\n");
+    result
+}
+
+fn format_impl_item(item: &ImplItem, indent: usize) -> String {
+    match *item {
+        ImplItem::Fn(ref f) => format_fn(&f.attrs, &f.vis, &f.sig, Some(&f.block), indent),
+        ImplItem::Type(ref t)
+            if t.generics.params.is_empty() && t.generics.where_clause.is_none() =>
+        {
+            let head = format!(
+                "{}{}{}type {}",
+                print_attributes(&t.attrs, indent),
+                " ".repeat(indent),
+                print_visibility(&t.vis),
+                t.ident
+            );
+            format_assignment(&head, &t.ty, indent)
+        }
+        ref item => {
+            let mut wrapper: ItemImpl = parse("impl __ {}");
+            wrapper.items.push(item.clone());
+            inner_lines(&unparse(Item::Impl(wrapper)), indent)
+        }
+    }
+}
+
+// rustfmt's `rewrite_assign_rhs`: a type that does not fit after `=` goes
+// on the next line if it fits there on one line
+fn format_assignment(head: &str, ty: &Type, indent: usize) -> String {
+    let used = last_line_width(head) + " = ".len();
+    let flat = print_type(ty);
+    if used + flat.len() + ";".len() <= MAX_WIDTH {
+        return format!("{} = {};\n", head, flat);
+    }
+    if indent + 4 + flat.len() + ";".len() <= MAX_WIDTH {
+        return format!("{} =\n{}    {};\n", head, " ".repeat(indent), flat);
+    }
+    format!("{} = {};\n", head, print_type_wrapped(ty, indent, used, 1))
+}
+
+// rustfmt's `format_trait`, for traits whose bounds fit on one line
+fn format_trait(item: &ItemTrait, indent: usize) -> String {
+    let pad = " ".repeat(indent);
+    let mut result = print_attributes(&item.attrs, indent);
+    let attributes_len = result.len();
+
+    result.push_str(&pad);
+    result.push_str(&print_visibility(&item.vis));
+    if item.unsafety.is_some() {
+        result.push_str("unsafe ");
+    }
+    if item.auto_token.is_some() {
+        result.push_str("auto ");
+    }
+    result.push_str(&format!("trait {}", item.ident));
+    result.push_str(&print_generics(&item.generics));
+    if !item.supertraits.is_empty() {
+        result.push_str(": ");
+        result.push_str(&print_bounds(&item.supertraits));
+    }
+
+    let predicates = where_predicates(&item.generics);
+    result.push_str(&print_where(&predicates, indent, false, None));
+
+    let multiline = result[attributes_len..].contains('\n');
+    if item.items.is_empty() && !multiline {
+        result.push_str(" {}\n");
+        return result;
+    }
+    if multiline {
+        result.push_str(&format!("\n{}{{", pad));
+    } else {
+        result.push_str(" {");
+    }
+
+    if item.items.is_empty() {
+        result.push_str(&format!("\n{}}}\n", pad));
+        return result;
+    }
+
+    result.push('\n');
+    for trait_item in item.items.iter() {
+        result.push_str(&format_trait_item(trait_item, indent + 4));
+    }
+    result.push_str(&pad);
+    result.push_str("}\n");
+    result
+}
+
+fn format_trait_item(item: &TraitItem, indent: usize) -> String {
+    match *item {
+        TraitItem::Fn(ref f) => format_fn(
+            &f.attrs,
+            &Visibility::Inherited,
+            &f.sig,
+            f.default.as_ref(),
+            indent,
+        ),
+        ref item => {
+            let mut wrapper: ItemTrait = parse("trait __ {}");
+            wrapper.items.push(item.clone());
+            inner_lines(&unparse(Item::Trait(wrapper)), indent)
+        }
+    }
+}
+
+// rustfmt's `rewrite_fn_base`: the parameters stay on the line of the
+// function name if the whole signature fits, otherwise they go one per
+// line
+fn format_fn(
+    attrs: &[Attribute],
+    vis: &Visibility,
+    sig: &Signature,
+    block: Option<&Block>,
+    indent: usize,
+) -> String {
+    let pad = " ".repeat(indent);
+    let mut result = print_attributes(attrs, indent);
+
+    if sig.generics.where_clause.is_some() || sig.variadic.is_some() {
+        // not emitted by bpb, left to prettyplease
+        let mut f: syn::ItemFn = parse("fn __() {}");
+        f.vis = vis.clone();
+        f.sig = sig.clone();
+        match block {
+            Some(block) => f.block = Box::new(block.clone()),
+            None => f.block.stmts.clear(),
+        }
+        result.push_str(&indented(&unparse(Item::Fn(f)), indent));
+        return result;
+    }
+
+    let mut head = format!("{}{}", pad, print_visibility(vis));
+    if sig.constness.is_some() {
+        head.push_str("const ");
+    }
+    if sig.asyncness.is_some() {
+        head.push_str("async ");
+    }
+    if sig.unsafety.is_some() {
+        head.push_str("unsafe ");
+    }
+    if let Some(ref abi) = sig.abi {
+        head.push_str("extern ");
+        if let Some(ref name) = abi.name {
+            head.push_str(&format!("{:?} ", name.value()));
+        }
+    }
+    head.push_str("fn ");
+    // rustfmt leaves room for `() {` after the parameters
+    let name = sig.ident.to_string();
+    let overhead = if block.is_some() { "() {" } else { "()" };
+    let budget = MAX_WIDTH.saturating_sub(head.len() + overhead.len() + name.len() + "<>".len());
+    head.push_str(&name);
+    head.push_str(&print_generics_wrapped(
+        &sig.generics,
+        indent + 4,
+        indent,
+        budget,
+    ));
+    head.push('(');
+
+    let params: Vec<String> = sig.inputs.iter().map(print_fn_arg).collect();
+    let tail = match block {
+        Some(block) if block.stmts.is_empty() => " {}",
+        Some(_) => " {",
+        None => ";",
+    };
+    // the return type is broken over several lines if it does not fit
+    // after the parameters
+    let output = |used: usize| match sig.output {
+        ReturnType::Default => String::new(),
+        ReturnType::Type(_, ref ty) => format!(
+            " -> {}",
+            print_type_wrapped(ty, indent, used + " -> ".len(), 0)
+        ),
+    };
+
+    let one_line = format!("{}{}){}{}", head, params.join(", "), output(0), tail);
+    let returns = match sig.output {
+        ReturnType::Default => false,
+        ReturnType::Type(..) => true,
+    };
+    if !head.contains('\n') && tail == ";" && returns && one_line.len() == MAX_WIDTH {
+        // rustfmt counts the semicolon twice against the return type, but
+        // not against the parameters
+        result.push_str(&format!(
+            "{}{})\n{}    ->{};",
+            head,
+            params.join(", "),
+            pad,
+            &output(0)[" ->".len()..]
+        ));
+    } else if !head.contains('\n') && one_line.len() <= MAX_WIDTH {
+        result.push_str(&one_line);
+    } else {
+        let mut signature = head;
+        for (arg, param) in sig.inputs.iter().zip(params.iter()) {
+            signature.push_str(&format!("\n{}    ", pad));
+            // a type that does not fit after the name of its parameter is
+            // broken like a return type
+            match *arg {
+                FnArg::Typed(ref typed) if indent + 4 + param.len() + 1 > MAX_WIDTH => {
+                    let mut bare = typed.clone();
+                    bare.ty = Box::new(parse("__"));
+                    let name = print_fn_arg(&FnArg::Typed(bare));
+                    let name = &name[..name.len() - "__".len()];
+                    let ty = print_type_wrapped(&typed.ty, indent + 4, indent + 4 + name.len(), 1);
+                    signature.push_str(name);
+                    signature.push_str(&ty);
+                }
+                _ => signature.push_str(param),
+            }
+            signature.push(',');
+        }
+        if !params.is_empty() {
+            signature.push_str(&format!("\n{}", pad));
+        }
+        signature.push(')');
+        // after parameters on their own lines rustfmt measures `-> ` from
+        // the indentation, not from the parenthesis
+        let used = if params.is_empty() {
+            last_line_width(&signature)
+        } else {
+            (indent + "-> ".len()).saturating_sub(" -> ".len())
+        };
+        signature.push_str(&output(used));
+        // the brace goes on a line of its own if it does not fit after the
+        // return type; rustfmt takes the indentation off the width of the
+        // last line twice
+        let width = if signature.contains('\n') {
+            MAX_WIDTH - indent
+        } else {
+            MAX_WIDTH
+        };
+        if tail == " {" && last_line_width(&signature) + tail.len() > width {
+            signature.push_str(&format!("\n{}{{", pad));
+        } else {
+            signature.push_str(tail);
+        }
+        result.push_str(&signature);
+    }
+    result.push('\n');
+
+    if let Some(block) = block {
+        if !block.stmts.is_empty() {
+            for stmt in block.stmts.iter() {
+                result.push_str(&format_stmt(stmt, indent + 4));
+            }
+            result.push_str(&pad);
+            result.push_str("}\n");
+        }
+    }
+    result
+}
+
+fn format_stmt(stmt: &Stmt, indent: usize) -> String {
+    let formatted = match *stmt {
+        Stmt::Macro(ref m) if m.attrs.is_empty() => format_macro(
+            &m.mac,
+            indent,
+            if m.semi_token.is_some() { ";" } else { "" },
+        ),
+        Stmt::Expr(Expr::Macro(ref m), ref semi) if m.attrs.is_empty() => {
+            format_macro(&m.mac, indent, if semi.is_some() { ";" } else { "" })
+        }
+        Stmt::Expr(Expr::Struct(ref e), ref semi) if e.attrs.is_empty() => {
+            format_struct_expr(e, indent, if semi.is_some() { ";" } else { "" })
+        }
+        Stmt::Expr(ref e @ Expr::MethodCall(_), ref semi) => {
+            format_chain(e, indent, if semi.is_some() { ";" } else { "" })
+        }
+        Stmt::Local(ref local) if local.attrs.is_empty() => format_let(local, indent),
+        _ => None,
+    };
+
+    formatted.unwrap_or_else(|| {
+        let mut wrapper: syn::ItemFn = parse("fn __() {}");
+        wrapper.block.stmts.push(stmt.clone());
+        inner_lines(&unparse(Item::Fn(wrapper)), indent)
+    })
+}
+
+/// rustfmt's `chain_width`: wider chains have one element per line.
+const CHAIN_WIDTH: usize = 60;
+
+// a chain wider than `chain_width` has one element per line, the first one
+// staying with a root as short as `self`
+fn format_chain(expr: &Expr, indent: usize, semi: &str) -> Option<String> {
+    let pad = " ".repeat(indent);
+    let flat = print_expr(expr)?;
+    if flat.len() <= CHAIN_WIDTH && indent + flat.len() + semi.len() <= MAX_WIDTH {
+        return Some(format!("{}{}{}\n", pad, flat, semi));
+    }
+
+    let mut elements = Vec::new();
+    let mut root = expr;
+    loop {
+        match *root {
+            Expr::MethodCall(ref call) => {
+                let mut element = call.clone();
+                element.receiver = Box::new(parse("__"));
+                elements.push(print_expr(&Expr::MethodCall(element))?["__".len()..].to_owned());
+                root = &call.receiver;
+            }
+            Expr::Field(ref field) => {
+                let mut element = field.clone();
+                element.base = Box::new(parse("__"));
+                elements.push(print_expr(&Expr::Field(element))?["__".len()..].to_owned());
+                root = &field.base;
+            }
+            Expr::Try(ref e) => {
+                elements.push("?".to_owned());
+                root = &e.expr;
+            }
+            _ => break,
+        }
+    }
+    elements.reverse();
+
+    let root = print_expr(root)?;
+    let mut s = format!("{}{}", pad, root);
+    let mut elements = elements.into_iter();
+    if root.len() <= 4 {
+        s.push_str(&elements.next()?);
+    }
+    for element in elements {
+        if element == "?" {
+            s.push('?');
+        } else {
+            s.push_str(&format!("\n{}    {}", pad, element));
+        }
+    }
+    s.push_str(semi);
+    s.push('\n');
+    Some(s)
+}
+
+// the declaration stays on one line, ending in ` =` if the value does not
+// fit after it
+fn format_let(local: &syn::Local, indent: usize) -> Option<String> {
+    let init = local.init.as_ref()?;
+    if init.diverge.is_some() {
+        return None;
+    }
+    let pad = " ".repeat(indent);
+
+    let mut declaration = local.clone();
+    declaration.init = None;
+    let mut wrapper: syn::ItemFn = parse("fn __() {}");
+    wrapper.block.stmts.push(Stmt::Local(declaration));
+    let printed = unparse(Item::Fn(wrapper));
+    let lines: Vec<&str> = printed.lines().collect();
+    // prettyplease breaks long types at its own, narrower, width
+    let declaration = flatten(&lines[1..lines.len() - 1].join("\n"));
+    let declaration = declaration.trim_end_matches(';');
+    if indent + declaration.len() + " =".len() > MAX_WIDTH {
+        return None;
+    }
+
+    let used = indent + declaration.len() + " = ".len();
+    let value = format_rhs(&init.expr, indent, used, ";".len())?;
+    Some(format!("{}{} ={};\n", pad, declaration, value))
+}
+
+// rustfmt's `choose_rhs`: the value of a `let` or of a field, starting at
+// column `used`, after a space if it fits there on one line, on the next
+// line if it fits there on one line, otherwise wherever it is broken over
+// fewer lines, preferring the same line
+fn format_rhs(expr: &Expr, indent: usize, used: usize, tail: usize) -> Option<String> {
+    let same = format_expr(expr, indent, used, tail);
+    if let Some(ref same) = same {
+        if !same.contains('\n') {
+            return Some(format!(" {}", same));
+        }
+    }
+    let next = format_expr(expr, indent + 4, indent + 4, tail)
+        .map(|next| format!("\n{}{}", " ".repeat(indent + 4), next));
+    match (same, next) {
+        (Some(same), Some(next)) => {
+            let first_end = |s: &str| s.trim_start().lines().next().unwrap_or("").chars().last();
+            let opens = |c: Option<char>| c == Some('(') || c == Some('[') || c == Some('{');
+            if !next.trim_start().contains('\n')
+                || same.matches('\n').count() > next.trim_start().matches('\n').count() + 1
+                || opens(first_end(&same)) && first_end(&same) != first_end(&next)
+            {
+                Some(next)
+            } else {
+                Some(format!(" {}", same))
+            }
+        }
+        (Some(same), None) => Some(format!(" {}", same)),
+        (None, next) => next,
+    }
+}
+
+/// rustfmt's `array_width`: wider arrays have one item per line.
+const ARRAY_WIDTH: usize = 60;
+
+// rustfmt's layout of an expression starting at column `used` of a line
+// indented by `indent`, followed by `tail` columns: on one line if it fits
+// and the arguments of its calls, or the items of its tuples and arrays,
+// are not too wide. Otherwise a single argument or item that can be broken
+// itself stays on the line of the call, or they go one per line with a
+// trailing comma, packed if they are all short and simple array items.
+// `None` if the expression cannot be laid out within the width.
+fn format_expr(expr: &Expr, indent: usize, used: usize, tail: usize) -> Option<String> {
+    let flat = print_expr(expr)?;
+    let fits = used + flat.len() + tail <= MAX_WIDTH;
+
+    let (open, items, close, width) = match *expr {
+        Expr::Call(ref call) => (
+            format!("{}(", print_expr(&call.func)?),
+            call.args.iter().cloned().collect::<Vec<Expr>>(),
+            ")",
+            FN_CALL_WIDTH,
+        ),
+        Expr::Tuple(ref tuple) if tuple.elems.len() > 1 => (
+            "(".to_owned(),
+            tuple.elems.iter().cloned().collect(),
+            ")",
+            FN_CALL_WIDTH,
+        ),
+        Expr::Array(ref array) => (
+            "[".to_owned(),
+            array.elems.iter().cloned().collect(),
+            "]",
+            ARRAY_WIDTH,
+        ),
+        Expr::Macro(ref m) if matches!(m.mac.delimiter, MacroDelimiter::Bracket(_)) => {
+            match m
+                .mac
+                .parse_body_with(Punctuated::<Expr, Comma>::parse_terminated)
+            {
+                Ok(items) => (
+                    format!("{}![", print_path(&m.mac.path)),
+                    items.into_iter().collect(),
+                    "]",
+                    ARRAY_WIDTH,
+                ),
+                Err(_) => return if fits { Some(flat) } else { None },
+            }
+        }
+        _ => return if fits { Some(flat) } else { None },
+    };
+
+    // only a single argument that cannot be broken itself may be wider
+    let overflowable = items.len() == 1
+        && match items[0] {
+            Expr::Call(_) | Expr::Array(_) => true,
+            Expr::Tuple(ref tuple) => tuple.elems.len() > 1,
+            Expr::Macro(ref m) => matches!(m.mac.delimiter, MacroDelimiter::Bracket(_)),
+            _ => false,
+        };
+    let inner = flat.len() - open.len() - close.len();
+    if items.is_empty() || fits && (inner <= width || items.len() == 1 && !overflowable) {
+        return Some(flat);
+    }
+
+    let pad = " ".repeat(indent);
+    if overflowable {
+        let item = format_expr(&items[0], indent, used + open.len(), close.len() + tail);
+        if let Some(item) = item.filter(|item| item.contains('\n')) {
+            return Some(format!("{}{}{}", open, item, close));
+        }
+    }
+
+    let nested = indent + 4;
+    let lines = if width == ARRAY_WIDTH
+        && items.len() > 1
+        && items.iter().all(|i| {
+            is_simple_expr(i) && print_expr(i).is_some_and(|i| i.len() <= SHORT_ITEM_WIDTH)
+        }) {
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        for item in items.iter().map(print_expr) {
+            let item = item?;
+            if !line.is_empty() && nested + line.len() + 2 + item.len() + 1 > MAX_WIDTH {
+                lines.push(line);
+                line = String::new();
+            }
+            if !line.is_empty() {
+                line.push_str(", ");
+            }
+            line.push_str(&item);
+        }
+        lines.push(line);
+        lines
+    } else {
+        items
+            .iter()
+            .map(|item| format_expr(item, nested, nested, ",".len()))
+            .collect::<Option<Vec<String>>>()?
+    };
+
+    let mut s = format!("{}\n", open);
+    for line in lines {
+        s.push_str(&format!("{}    {},\n", pad, line));
+    }
+    s.push_str(&format!("{}{}", pad, close));
+    if indent + close.len() + tail > MAX_WIDTH || used + open.len() > MAX_WIDTH {
+        return None;
+    }
+    Some(s)
+}
+
+/// rustfmt's `struct_lit_width`: struct literals with wider fields are laid
+/// out vertically.
+const STRUCT_LIT_WIDTH: usize = 18;
+
+// rustfmt lays out the fields of a struct literal one per line unless they
+// are very short; a field is broken over several lines only if it does not
+// fit on one, as prettyplease would break it
+fn format_struct_expr(expr: &syn::ExprStruct, indent: usize, semi: &str) -> Option<String> {
+    if expr.qself.is_some() {
+        return None;
+    }
+    let pad = " ".repeat(indent);
+    let path = print_path(&expr.path);
+
+    let mut fields = Vec::new();
+    for field in expr.fields.iter() {
+        let member = match field.member {
+            syn::Member::Named(ref ident) => ident.to_string(),
+            syn::Member::Unnamed(ref index) => index.index.to_string(),
+        };
+        match field.colon_token {
+            Some(_) => fields.push((member, print_expr(&field.expr), field)),
+            None => fields.push((member.clone(), Some(member), field)),
+        }
+    }
+    let rest = match expr.rest {
+        Some(ref rest) => Some(format!("..{}", print_expr(rest)?)),
+        None if expr.dot2_token.is_some() => Some("..".to_owned()),
+        None => None,
+    };
+
+    if fields.is_empty() && rest.is_none() {
+        return Some(format!("{}{} {{}}{}\n", pad, path, semi));
+    }
+
+    let flat = fields
+        .iter()
+        .map(
+            |&(ref member, ref value, field)| match (value, field.colon_token) {
+                (Some(value), Some(_)) => Some(format!("{}: {}", member, value)),
+                (&Some(_), None) => Some(member.clone()),
+                (&None, _) => None,
+            },
+        )
+        .chain(rest.clone().map(Some))
+        .collect::<Option<Vec<String>>>();
+    if let Some(flat) = flat {
+        let body = flat.join(", ");
+        let line = format!("{}{} {{ {} }}{}", pad, path, body, semi);
+        if body.len() <= STRUCT_LIT_WIDTH && line.len() <= MAX_WIDTH {
+            return Some(format!("{}\n", line));
+        }
+    }
+
+    let inner = " ".repeat(indent + 4);
+    let mut s = format!("{}{} {{\n", pad, path);
+    for &(ref member, ref value, field) in fields.iter() {
+        let line = match (value, field.colon_token) {
+            (Some(value), Some(_)) => format!("{}{}: {},", inner, member, value),
+            _ => format!("{}{},", inner, member),
+        };
+        let used = indent + 4 + member.len() + ": ".len();
+        if value.is_some() && line.len() <= MAX_WIDTH {
+            s.push_str(&line);
+            s.push('\n');
+        } else if let Some(value) = value
+            .as_ref()
+            .and_then(|_| format_rhs(&field.expr, indent + 4, used, ",".len()))
+        {
+            s.push_str(&format!("{}{}:{},\n", inner, member, value));
+        } else {
+            let mut single = expr.clone();
+            single.fields.clear();
+            single.fields.push(field.clone());
+            single.dot2_token = None;
+            single.rest = None;
+            let mut wrapper: syn::ItemFn = parse("fn __() {}");
+            wrapper
+                .block
+                .stmts
+                .push(Stmt::Expr(Expr::Struct(single), None));
+            let printed = unparse(Item::Fn(wrapper));
+            let lines: Vec<&str> = printed.lines().collect();
+            let field_lines = &lines[2..lines.len() - 2];
+            if field_lines.len() == 1 && value.is_some() {
+                // a value prettyplease does not break goes on the next line
+                s.push_str(&format!(
+                    "{}{}:\n{}    {},\n",
+                    inner,
+                    member,
+                    inner,
+                    value.as_ref().unwrap()
+                ));
+            } else {
+                s.push_str(&indented(&dedent(&field_lines.join("\n"), 8), indent + 4));
+            }
+        }
+    }
+    if let Some(rest) = rest {
+        s.push_str(&format!("{}{}\n", inner, rest));
+    }
+    s.push_str(&format!("{}}}{}\n", pad, semi));
+    Some(s)
+}
+
+// rustfmt lays out the arguments of a macro called with parentheses like
+// those of a function call: on one line if they are short enough,
+// otherwise one per line, or packed if they are all short and simple. A
+// few macros keep their format string apart. The trailing comma is kept
+// as written, as not every macro accepts one.
+fn format_macro(mac: &Macro, indent: usize, semi: &str) -> Option<String> {
+    match mac.delimiter {
+        MacroDelimiter::Paren(_) => {}
+        _ => return None,
+    }
+    let args = mac
+        .parse_body_with(Punctuated::<Expr, Comma>::parse_terminated)
+        .ok()?;
+    let items = args
+        .iter()
+        .map(print_expr)
+        .collect::<Option<Vec<String>>>()?;
+
+    let pad = " ".repeat(indent);
+    let name = format!("{}!", print_path(&mac.path));
+    let trailing = if args.trailing_punct() { "," } else { "" };
+
+    let horizontal = items.join(", ");
+    let width = indent + name.len() + 2 + horizontal.len() + trailing.len() + semi.len();
+    if items.is_empty()
+        || width <= MAX_WIDTH && (items.len() == 1 || horizontal.len() <= FN_CALL_WIDTH)
+    {
+        return Some(format!(
+            "{}{}({}{}){}\n",
+            pad, name, horizontal, trailing, semi
+        ));
+    }
+
+    // the arguments are indented once more and followed by a comma
+    let nested_width = MAX_WIDTH - indent - 4 - 1;
+    let fits =
+        |items: &[String]| items.iter().map(|i| i.len() + 2).sum::<usize>() <= nested_width + 2;
+    let all_simple = args.iter().all(is_simple_expr);
+    let special = SPECIAL_MACROS
+        .iter()
+        .find(|&&(special, _)| special == name)
+        .map(|&(_, before)| before);
+
+    let mut lines: Vec<String> = Vec::new();
+    match special {
+        Some(before)
+            if all_simple
+                && items.len() > before
+                && fits(&items[..before])
+                && fits(&items[before + 1..]) =>
+        {
+            if before > 0 {
+                lines.push(items[..before].join(", "));
+            }
+            lines.push(items[before].clone());
+            if items.len() > before + 1 {
+                lines.push(items[before + 1..].join(", "));
+            }
+        }
+        None if all_simple && items.iter().all(|i| i.len() <= SHORT_ITEM_WIDTH) => {
+            let mut line = String::new();
+            for item in items.iter() {
+                if !line.is_empty() && line.len() + 2 + item.len() > nested_width {
+                    lines.push(line);
+                    line = String::new();
+                }
+                if !line.is_empty() {
+                    line.push_str(", ");
+                }
+                line.push_str(item);
+            }
+            lines.push(line);
+        }
+        _ => lines = items,
+    }
+
+    let mut s = format!("{}{}(\n", pad, name);
+    let last = lines.len() - 1;
+    for (i, line) in lines.iter().enumerate() {
+        let comma = if i < last { "," } else { trailing };
+        s.push_str(&format!("{}    {}{}\n", pad, line, comma));
+    }
+    s.push_str(&format!("{}){}\n", pad, semi));
+    Some(s)
+}
+
+// rustfmt's `is_simple_expr`
+fn is_simple_expr(expr: &Expr) -> bool {
+    match *expr {
+        Expr::Lit(_) => true,
+        Expr::Path(ref e) => e.qself.is_none() && e.path.segments.len() <= 1,
+        Expr::Reference(ref e) => is_simple_expr(&e.expr),
+        Expr::Cast(ref e) => is_simple_expr(&e.expr),
+        Expr::Field(ref e) => is_simple_expr(&e.base),
+        Expr::Try(ref e) => is_simple_expr(&e.expr),
+        Expr::Unary(ref e) => is_simple_expr(&e.expr),
+        Expr::Index(ref e) => is_simple_expr(&e.expr) && is_simple_expr(&e.index),
+        Expr::Repeat(ref e) => is_simple_expr(&e.expr) && is_simple_expr(&e.len),
+        _ => false,
+    }
+}
+
+// rustfmt's `rewrite_where_clause`: `where` on a line of its own, unless
+// it follows a lone `>`, then one bound per line. `single_line`, for an
+// empty impl with a single bound, keeps `where` on the header line and the
+// bound too if it fits in the budget.
+fn print_where(
+    predicates: &[String],
+    indent: usize,
+    after_bracket: bool,
+    single_line: Option<usize>,
+) -> String {
+    if predicates.is_empty() {
+        return String::new();
+    }
+
+    let pad = " ".repeat(indent);
+    if let Some(budget) = single_line {
+        let predicate = &predicates[0];
+        if "where ".len() + predicate.len() <= budget {
+            return format!(" where {}", predicate);
+        }
+        return format!(" where\n{}    {}", pad, predicate);
+    }
+
+    let mut s = if after_bracket {
+        String::from(" where")
+    } else {
+        format!("\n{}where", pad)
+    };
+    for predicate in predicates.iter() {
+        s.push_str(&format!("\n{}    {},", pad, predicate));
+    }
+    s
+}
+
+fn where_predicates(generics: &Generics) -> Vec<String> {
+    match generics.where_clause {
+        Some(ref where_clause) => where_clause
+            .predicates
+            .iter()
+            .map(print_predicate)
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+fn print_predicate(predicate: &WherePredicate) -> String {
+    let mut item: ItemImpl = parse("impl __ where __: __ {}");
+    let where_clause = item.generics.where_clause.as_mut().unwrap();
+    where_clause.predicates.clear();
+    where_clause.predicates.push(predicate.clone());
+    let s = flatten(&unparse(Item::Impl(item)));
+    s["impl __ where ".len()..s.len() - " {}".len()]
+        .trim_end_matches(',')
+        .to_owned()
+}
+
+fn print_generics(generics: &Generics) -> String {
+    if generics.params.is_empty() {
+        return String::new();
+    }
+    let mut item: syn::ItemType = parse("type __ = ();");
+    item.generics.params = generics.params.clone();
+    let s = flatten(&unparse(Item::Type(item)));
+    s["type __".len()..s.len() - " = ();".len()].to_owned()
+}
+
+// the parameters one per line, at `indent`, followed by `>` at `close`, if
+// they are wider than `budget`
+fn print_generics_wrapped(
+    generics: &Generics,
+    indent: usize,
+    close: usize,
+    budget: usize,
+) -> String {
+    let flat = print_generics(generics);
+    if flat.len() <= budget + 2 {
+        return flat;
+    }
+
+    let mut s = String::from("<");
+    for param in generics.params.iter() {
+        let mut single = Generics::default();
+        single.params.push(param.clone());
+        let param = print_generics(&single);
+        s.push_str(&format!(
+            "\n{}{},",
+            " ".repeat(indent),
+            &param[1..param.len() - 1]
+        ));
+    }
+    s.push_str(&format!("\n{}>", " ".repeat(close)));
+    s
+}
+
+// `ty`, starting `used` columns into a line at `indent` and followed by
+// `tail` columns; if it does not fit, the generic arguments of its last
+// segment go one per line
+fn print_type_wrapped(ty: &Type, indent: usize, used: usize, tail: usize) -> String {
+    let flat = print_type(ty);
+    if used + flat.len() + tail <= MAX_WIDTH {
+        return flat;
+    }
+
+    let path = match *ty {
+        Type::Path(ref path) if path.qself.is_none() => path,
+        _ => return flat,
+    };
+    let mut bare = path.clone();
+    let arguments = match bare.path.segments.last_mut() {
+        Some(segment) => std::mem::replace(&mut segment.arguments, PathArguments::None),
+        None => return flat,
+    };
+    let arguments = match arguments {
+        PathArguments::AngleBracketed(arguments) => arguments,
+        _ => return flat,
+    };
+
+    let mut s = print_type(&Type::Path(bare));
+    s.push('<');
+    for argument in arguments.args.iter() {
+        let mut single = arguments.clone();
+        single.args.clear();
+        single.args.push(argument.clone());
+        let mut wrapper: syn::TypePath = parse("__");
+        wrapper.path.segments[0].arguments = PathArguments::AngleBracketed(single);
+        let argument = print_type(&Type::Path(wrapper));
+        s.push_str(&format!(
+            "\n{}    {},",
+            " ".repeat(indent),
+            &argument["__<".len()..argument.len() - 1]
+        ));
+    }
+    s.push_str(&format!("\n{}>", " ".repeat(indent)));
+    s
+}
+
+fn print_bounds(bounds: &Punctuated<TypeParamBound, syn::token::Plus>) -> String {
+    let mut ty: syn::TypeImplTrait = parse("impl __");
+    ty.bounds = bounds.clone();
+    print_type(&Type::ImplTrait(ty))["impl ".len()..].to_owned()
+}
+
+fn print_type(ty: &Type) -> String {
+    let mut item: syn::ItemType = parse("type __ = ();");
+    item.ty = Box::new(ty.clone());
+    let s = flatten(&unparse(Item::Type(item)));
+    s["type __ = ".len()..s.len() - 1].to_owned()
+}
+
+fn print_path(path: &Path) -> String {
+    print_type(&Type::Path(syn::TypePath {
+        qself: None,
+        path: path.clone(),
+    }))
+}
+
+fn print_fn_arg(arg: &FnArg) -> String {
+    let mut item: syn::ItemFn = parse("fn __() {}");
+    item.sig.inputs.push(arg.clone());
+    let s = flatten(&unparse(Item::Fn(item)));
+    s["fn __(".len()..s.len() - ") {}".len()].to_owned()
+}
+
+fn print_visibility(vis: &Visibility) -> String {
+    let mut item: syn::ItemStruct = parse("struct __;");
+    item.vis = vis.clone();
+    let s = unparse(Item::Struct(item));
+    s[..s.len() - "struct __;\n".len()].to_owned()
+}
+
+fn print_attributes(attrs: &[Attribute], indent: usize) -> String {
+    if attrs.is_empty() {
+        return String::new();
+    }
+    let mut item: syn::ItemStruct = parse("struct __;");
+    item.attrs = attrs.to_vec();
+    let s = unparse(Item::Struct(item));
+    indented(&s[..s.len() - "struct __;\n".len()], indent)
+}
+
+// an expression on one line; prettyplease breaks lines earlier than rustfmt
+// so its output is joined back, unless that would change the expression
+fn print_expr(expr: &Expr) -> Option<String> {
+    let printed = unparse_expr(expr);
+    if !printed.contains('\n') {
+        return Some(printed);
+    }
+    let flat = flatten(&printed);
+    let reparsed: Expr = syn::parse_str(&flat).ok()?;
+    if unparse_expr(&reparsed) == printed {
+        Some(flat)
+    } else {
+        None
+    }
+}
+
+fn unparse_expr(expr: &Expr) -> String {
+    let mut item: syn::ItemConst = parse("const _: () = ();");
+    item.expr = Box::new(expr.clone());
+    let s = unparse(Item::Const(item));
+    let s = s.trim_end();
+    s["const _: () = ".len()..s.len() - 1].to_owned()
+}
+
+fn parse<T: syn::parse::Parse>(code: &str) -> T {
+    syn::parse_str(code).unwrap()
+}
+
+fn unparse(item: Item) -> String {
+    prettyplease::unparse(&syn::File {
+        shebang: None,
+        attrs: Vec::new(),
+        items: vec![item],
+    })
+}
+
+// joins what prettyplease broke over several lines back into one
+fn flatten(s: &str) -> String {
+    let mut out = String::new();
+    for line in s.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if line.starts_with(&['>', ')', ']', '}'][..]) {
+            if out.ends_with(',') {
+                out.pop();
+            }
+            if line.starts_with('}') {
+                out.push(' ');
+            }
+        } else {
+            let joined = line.starts_with('?') || line.starts_with('.') && !line.starts_with("..");
+            if !joined && !out.is_empty() && !out.ends_with(&['<', '(', '['][..]) {
+                out.push(' ');
+            }
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+fn indented(s: &str, indent: usize) -> String {
+    let pad = " ".repeat(indent);
+    let mut out = String::new();
+    for line in s.lines() {
+        if !line.is_empty() {
+            out.push_str(&pad);
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+// the lines of the body of a single wrapping item, whose own indentation
+// is replaced by `indent`
+fn inner_lines(s: &str, indent: usize) -> String {
+    let lines: Vec<&str> = s.lines().collect();
+    let inner = lines[1..lines.len() - 1]
+        .iter()
+        .map(|l| l.strip_prefix("    ").unwrap_or(l))
+        .collect::<Vec<&str>>()
+        .join("\n");
+    indented(&inner, indent)
+}
+
+fn dedent(s: &str, width: usize) -> String {
+    s.lines()
+        .map(|l| {
+            if l.len() >= width {
+                &l[width..]
+            } else {
+                l.trim_start()
+            }
+        })
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+fn last_line_width(s: &str) -> usize {
+    s.rsplit('\n').next().unwrap_or("").len()
+}
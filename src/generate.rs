@@ -0,0 +1,996 @@
+use error::Error;
+use format::format;
+use generics::Generics;
+use model::{
+    Catalog, Collection, ExtraType, Field, Finalizer, Setter, Struct, Typestate, YesNo, PRIVATE,
+};
+use naming::apply_naming;
+use prelude::{calculate_prelude, calculate_uses};
+use traits::{calculate_catalog_traits, calculate_traits};
+use validate::validate;
+
+/// Generates the source code of the builder described by `stc`.
+///
+/// The definition is validated first, once its naming convention has been
+/// applied; if it has problems they are returned as `Error::Invalid` and
+/// nothing is generated. The code is formatted the way `rustfmt` formats it,
+/// except for the bodies of the finalizers which are kept as written, only
+/// indented to their place.
+pub fn generate(stc: &Struct) -> Result<String, Error> {
+    let code = format(&generate_code(stc, true)?)?;
+    Ok(restore_bodies(&code, &stc.finalizers))
+}
+
+/// Generates the definitions of the traits of `catalog`, formatted like
+/// [`generate`] output, to be placed in the catalog's shared module.
+pub fn generate_catalog(catalog: &Catalog) -> Result<String, Error> {
+    format(&calculate_catalog_traits(catalog)?)
+}
+
+/// Generates the typestate prelude configured by `typestate`, formatted
+/// like [`generate`] output, to be placed in the module named by the
+/// builders' `prelude_path`.
+pub fn generate_prelude(typestate: &Typestate) -> Result<String, Error> {
+    format(&calculate_prelude(typestate))
+}
+
+/// Like [`generate`], but skips formatting. Useful when the code is only
+/// going to be parsed, as in a procedural macro. The code is still parsed
+/// once as a check, returning `Error::Syntax` if it is not valid Rust.
+pub fn generate_unformatted(stc: &Struct) -> Result<String, Error> {
+    generate_code(stc, false)
+}
+
+// with `placeholders`, the body of each finalizer is replaced by a
+// placeholder, put back by `restore_bodies` once the code is formatted
+fn generate_code(stc: &Struct, placeholders: bool) -> Result<String, Error> {
+    let named;
+    let stc = if stc.naming.is_some() {
+        let mut copy = stc.clone();
+        apply_naming(&mut copy);
+        named = copy;
+        &named
+    } else {
+        stc
+    };
+
+    let diagnostics = validate(stc);
+    if !diagnostics.is_empty() {
+        return Err(Error::Invalid(diagnostics));
+    }
+
+    let all_builder_types = {
+        let mut abt = Vec::new();
+        stc.fields
+            .iter()
+            .filter(|f| !f.optional())
+            .for_each(|bt| abt.push(bt.builder_type.clone().unwrap()));
+        abt
+    };
+
+    let mut output = String::new();
+
+    let mut regardless = String::new();
+
+    // dump uses
+    let uses = stc
+        .uses
+        .iter()
+        .cloned()
+        .chain(calculate_uses(stc))
+        .collect::<Vec<_>>();
+    if !uses.is_empty() {
+        uses.iter().for_each(|u| {
+            if u.ends_with(';') {
+                output.push_str(&format!("use {}\n", u))
+            } else {
+                output.push_str(&format!("use {};\n", u))
+            }
+        });
+
+        output.push('\n');
+    }
+
+    // prepend mandatory types if required
+    if stc.prepend_required_types.unwrap_or_default() {
+        output.push_str(&calculate_prelude(&stc.typestate));
+        output.push('\n');
+    }
+
+    // getter and setter traits, if requested
+    let traits = if stc.emit_traits() {
+        calculate_traits(stc)
+    } else {
+        String::new()
+    };
+    if !traits.is_empty() {
+        output.push_str("// getter and setter traits\n");
+        output.push_str(&traits);
+    }
+
+    // dump derives, if any
+    if let Some(ref derive) = stc.derive {
+        output.push_str(&format!("#[derive({})]\n", derive));
+    }
+    // create the struct
+    {
+        output.push_str(&format!(
+            "{}struct {}{}\n{} {{\n",
+            calculate_visibility(stc, &None),
+            stc.name,
+            calculate_generic_params(stc, &[]),
+            calculate_where(stc, &[])
+        ));
+
+        // constructor types
+        for t in stc.constructor_fields.iter() {
+            output.push_str(&format!("\t{}: {},\n", t.name, t.field_type));
+        }
+
+        // phantom types
+        for f in stc.fields.iter().filter(|f| !f.optional()) {
+            output.push_str(&format!(
+                "\tp_{}: PhantomData<{}>,\n",
+                f.name,
+                f.clone().builder_type.unwrap()
+            ));
+        }
+
+        for f in &stc.fields {
+            output.push_str(&format!("\t{}: {},\n", f.name, calculate_type(f)));
+        }
+
+        output.push_str("}\n\n");
+    }
+
+    // create the ctor
+    {
+        output.push_str(&format!(
+            "impl{} {}{} {} {{\n",
+            calculate_generic_params(stc, &all_builder_types),
+            stc.name,
+            calculate_type_description_all(stc, YesNo::No),
+            calculate_where(stc, &all_builder_types)
+        ));
+
+        if stc.inline() {
+            output.push_str("#[inline]\n");
+        }
+
+        output.push_str(&format!(
+            "\t{}fn new({}) -> {}{} {{\n\t\t{} {{\n",
+            calculate_new_visibility(stc),
+            calculate_constructor_parameters(stc),
+            stc.name,
+            calculate_type_description_all(stc, YesNo::No),
+            stc.name
+        ));
+
+        for cp in stc.constructor_fields.iter() {
+            output.push_str(&format!("\t\t\t{},\n", cp.name));
+        }
+
+        for f in stc.fields.iter().filter(|f| !f.optional()) {
+            output.push_str(&format!("\t\t\tp_{}: PhantomData {{}},\n", f.name));
+            match f.initializer() {
+                Some(initializer) => {
+                    output.push_str(&format!("\t\t\t{}: {},\n", f.name, initializer))
+                }
+                None => output.push_str(&format!("\t\t\t{}: None,\n", f.name)),
+            };
+        }
+
+        for f in stc.fields.iter().filter(|f| f.optional()) {
+            match f.initializer() {
+                Some(initializer) => {
+                    output.push_str(&format!("\t\t\t{}: {},\n", f.name, initializer))
+                }
+                None => output.push_str(&format!("\t\t\t{}: None,\n", f.name)),
+            };
+        }
+
+        output.push_str("\t\t}\n\t}\n}\n\n");
+    }
+
+    // constructor types getter
+    {
+        // first the one with trait
+        for ct in stc
+            .constructor_fields
+            .iter()
+            .filter(|ct| ct.trait_get.is_some())
+        {
+            let t = ct.trait_get.clone().unwrap();
+            output.push_str(&format!(
+                "impl{} {} for {}{}\n",
+                calculate_generic_params(stc, &[]),
+                t,
+                stc.name,
+                calculate_type_description(stc, &[], None),
+            ));
+
+            output.push_str(&format!("{}{{\n", &calculate_where(stc, &[])));
+
+            if stc.inline() {
+                output.push_str("#[inline]\n");
+            }
+
+            // get_via_clone handling
+            let get_via_clone = ct.get_via_clone.unwrap_or_default();
+
+            output.push_str(&format!(
+                "\tfn {}(&self) -> {} {{\n\t\tself.{}{}\n\t}}\n\n",
+                ct.name,
+                ct.field_type,
+                ct.name,
+                if get_via_clone { ".clone()" } else { "" }
+            ));
+
+            output.push_str("}\n\n");
+        }
+
+        // now the ones without trait
+        for ct in stc
+            .constructor_fields
+            .iter()
+            .filter(|ct| ct.trait_get.is_none())
+        {
+            // get_via_clone handling
+            let get_via_clone = ct.get_via_clone.unwrap_or_default();
+
+            if stc.inline() {
+                regardless.push_str("#[inline]\n");
+            }
+            regardless.push_str(&format!(
+                "\t{}fn {}(&self) -> {} {{\n\t\tself.{}{}\n\t}}\n\n",
+                calculate_visibility(stc, &ct.visibility),
+                ct.name,
+                ct.field_type,
+                ct.name,
+                if get_via_clone { ".clone()" } else { "" }
+            ));
+        }
+    }
+
+    // get mandatory no traits methods
+    let mandatory_no_traits: Vec<&Field> = stc
+        .fields
+        .iter()
+        .filter(|tm| tm.trait_get.is_none() && !tm.optional())
+        .collect();
+    if !mandatory_no_traits.is_empty() {
+        output.push_str("\n// get mandatory no traits methods\n");
+        for &tm in mandatory_no_traits.iter() {
+            let bt = match tm.clone().builder_type {
+                Some(bt) => vec![bt],
+                None => Vec::new(),
+            };
+
+            output.push_str(&format!(
+                "impl{} {}{}\n",
+                calculate_generic_params(stc, &bt[..]),
+                stc.name,
+                calculate_type_description(stc, &bt[..], Some(YesNo::Yes)),
+            ));
+
+            output.push_str(&format!("{}\n{{\n", calculate_where(stc, &bt[..])));
+
+            if stc.inline() {
+                output.push_str("#[inline]\n");
+            }
+            output.push_str(&format!(
+                "\t{}fn {}(&self) -> ",
+                calculate_visibility(stc, &tm.visibility),
+                tm.name
+            ));
+
+            output.push_str(&format!(
+                "{} {{\n\t\t{}\n\t}}\n}}\n\n",
+                calculate_getter_type(tm),
+                calculate_getter_body(tm)
+            ));
+        }
+    }
+
+    // set mandatory no trait methods
+    if !mandatory_no_traits.is_empty() {
+        output.push_str("\n// set mandatory no traits methods\n");
+        for &tm in mandatory_no_traits.iter() {
+            let bt = match tm.clone().builder_type {
+                Some(bt) => vec![bt],
+                None => Vec::new(),
+            };
+
+            output.push_str(&format!(
+                "impl{} {}{}\n",
+                calculate_generic_params(stc, &bt[..]),
+                stc.name,
+                calculate_type_description(stc, &bt[..], Some(YesNo::No)),
+            ));
+
+            output.push_str(&format!("{}\n{{\n", calculate_where(stc, &bt[..])));
+
+            let return_type = format!(
+                "{}{}",
+                stc.name,
+                calculate_type_description(stc, &bt[..], Some(YesNo::Yes))
+            );
+
+            if stc.inline() {
+                output.push_str("#[inline]\n");
+            }
+            output.push_str(&format!(
+                "\t{}fn {} -> {} {{\n",
+                calculate_visibility(stc, &tm.visibility),
+                calculate_setter(stc, tm),
+                return_type
+            ));
+            output.push_str(&calculate_setter_conversion(tm));
+
+            output.push_str(&format!("\t\t{} {{\n", stc.name));
+
+            // constructor types
+            for t in stc.constructor_fields.iter() {
+                output.push_str(&format!("\t\t\t\t{}: self.{},\n", t.name, t.name));
+            }
+
+            // phantom types
+            for f in stc.fields.iter().filter(|f| !f.optional()) {
+                output.push_str(&format!("\t\t\t\tp_{}: PhantomData{{}},\n", f.name,));
+            }
+
+            for f in &stc.fields {
+                if f.name == tm.name {
+                    if tm.initializer().is_some() {
+                        output.push_str(&format!("\t\t\t\t{},\n", f.name));
+                    } else {
+                        output.push_str(&format!("\t\t\t\t{}: Some({}),\n", f.name, f.name));
+                    }
+                } else {
+                    output.push_str(&format!("\t\t\t\t{}: self.{},\n", f.name, f.name));
+                }
+            }
+
+            output.push_str("\t\t}\n\t}\n}\n\n");
+        }
+    }
+
+    // get traits methods
+    {
+        for tm in stc.fields.iter().filter(|tm| tm.trait_get.is_some()) {
+            let bt = match tm.clone().builder_type {
+                Some(bt) => vec![bt],
+                None => Vec::new(),
+            };
+            let tg = tm.trait_get.clone().unwrap();
+
+            output.push_str(&format!(
+                "impl{} {} for {}{}\n",
+                calculate_generic_params(stc, &bt[..]),
+                tg,
+                stc.name,
+                calculate_type_description(stc, &bt[..], Some(YesNo::Yes)),
+            ));
+
+            output.push_str(&format!("{}\n{{\n", calculate_where(stc, &bt[..])));
+
+            if stc.inline() {
+                output.push_str("#[inline]\n");
+            }
+            output.push_str(&format!("\tfn {}(&self) -> ", tm.name));
+
+            output.push_str(&format!(
+                "{} {{\n\t\t{}\n\t}}\n}}\n\n",
+                calculate_getter_type(tm),
+                calculate_getter_body(tm)
+            ));
+        }
+    }
+
+    // set trait methods
+    {
+        for tm in stc.fields.iter().filter(|tm| tm.trait_get.is_some()) {
+            let bt = match tm.clone().builder_type {
+                Some(bt) => vec![bt],
+                None => Vec::new(),
+            };
+            let tg = tm.trait_set.clone().unwrap();
+
+            output.push_str(&format!(
+                "impl{} {} for {}{}\n",
+                calculate_generic_params(stc, &bt[..]),
+                tg,
+                stc.name,
+                calculate_type_description(stc, &bt[..], Some(YesNo::No)),
+            ));
+
+            output.push_str(&format!("{}\n{{\n", calculate_where(stc, &bt[..])));
+
+            output.push_str(&format!(
+                "\ttype O = {}{};\n\n",
+                stc.name,
+                calculate_type_description(stc, &bt[..], Some(YesNo::Yes))
+            ));
+
+            if stc.inline() {
+                output.push_str("#[inline]\n");
+            }
+            output.push_str(&format!(
+                "\tfn {} -> Self::O {{\n",
+                calculate_setter(stc, tm)
+            ));
+            output.push_str(&calculate_setter_conversion(tm));
+
+            output.push_str(&format!("\t\t{} {{\n", stc.name));
+
+            // constructor types
+            for t in stc.constructor_fields.iter() {
+                output.push_str(&format!("\t\t\t\t{}: self.{},\n", t.name, t.name));
+            }
+
+            // phantom types
+            for f in stc.fields.iter().filter(|f| !f.optional()) {
+                output.push_str(&format!("\t\t\t\tp_{}: PhantomData{{}},\n", f.name,));
+            }
+
+            for f in &stc.fields {
+                if f.name == tm.name {
+                    if tm.initializer().is_some() {
+                        output.push_str(&format!("\t\t\t\t{},\n", f.name));
+                    } else {
+                        output.push_str(&format!("\t\t\t\t{}: Some({}),\n", f.name, f.name));
+                    }
+                } else {
+                    output.push_str(&format!("\t\t\t\t{}: self.{},\n", f.name, f.name));
+                }
+            }
+
+            output.push_str("\t\t}\n\t}\n}\n\n");
+        }
+    }
+
+    // get optional without traits
+    {
+        for tm in stc
+            .fields
+            .iter()
+            .filter(|tm| tm.optional() && tm.trait_get.is_none())
+        {
+            if stc.inline() {
+                regardless.push_str("#[inline]\n");
+            }
+            regardless.push_str(&format!(
+                "\t{}fn {}(&self) -> {} {{\n\t\t{}\n\t}}\n\n",
+                calculate_visibility(stc, &tm.visibility),
+                tm.name,
+                calculate_getter_type(tm),
+                calculate_getter_body(tm)
+            ));
+        }
+    }
+
+    // set optional without traits
+    {
+        for tm in stc
+            .fields
+            .iter()
+            .filter(|tm| tm.optional() && tm.trait_get.is_none())
+        {
+            if stc.inline() {
+                regardless.push_str("#[inline]\n");
+            }
+            regardless.push_str(&format!(
+                "\t{}fn {} -> Self {{\n",
+                calculate_visibility(stc, &tm.visibility),
+                calculate_setter(stc, tm)
+            ));
+            regardless.push_str(&calculate_setter_conversion(tm));
+
+            regardless.push_str(&format!("\t\t{} {{\n", stc.name));
+
+            // constructor types
+            for t in stc.constructor_fields.iter() {
+                regardless.push_str(&format!("\t\t\t\t{}: self.{},\n", t.name, t.name));
+            }
+
+            // phantom types
+            for f in stc.fields.iter().filter(|f| !f.optional()) {
+                regardless.push_str(&format!("\t\t\t\tp_{}: self.p_{},\n", f.name, f.name));
+            }
+
+            for f in &stc.fields {
+                if f.name == tm.name {
+                    if tm.initializer().is_some() {
+                        regardless.push_str(&format!("\t\t\t\t{},\n", f.name));
+                    } else {
+                        regardless.push_str(&format!("\t\t\t\t{}: Some({}),\n", f.name, f.name));
+                    }
+                } else {
+                    regardless.push_str(&format!("\t\t\t\t{}: self.{},\n", f.name, f.name));
+                }
+            }
+
+            regardless.push_str("\t\t}\n\t}\n\n");
+        }
+    }
+
+    // collection methods
+    {
+        for tm in stc.fields.iter().filter(|tm| tm.collection()) {
+            regardless.push_str(&calculate_collection_methods(stc, tm));
+        }
+    }
+
+    // print regardless
+    {
+        output.push_str("// methods callable regardless\n");
+        output.push_str(&format!(
+            "impl{} {}{}\n",
+            calculate_generic_params(stc, &[]),
+            stc.name,
+            calculate_type_description(stc, &[], None)
+        ));
+
+        output.push_str(&format!("{}\n", calculate_where(stc, &[])));
+
+        output.push_str(&format!("{{\n{}\n", &regardless));
+        output.push_str("}\n");
+    }
+
+    // print final
+    {
+        output.push_str("\n// methods callable only when every mandatory field has been filled\n");
+        output.push_str(&format!(
+            "impl{} {}{}\n",
+            calculate_generic_params(stc, &all_builder_types),
+            stc.name,
+            calculate_type_description_all(stc, YesNo::Yes),
+        ));
+
+        output.push_str(&format!("{}\n", calculate_where(stc, &all_builder_types)));
+
+        output.push_str("{\n");
+        for (i, fin) in stc.finalizers.iter().enumerate() {
+            if stc.inline() {
+                output.push_str("#[inline]\n");
+            }
+            match fin.body {
+                Some(ref body) if placeholders && !body.trim().is_empty() => {
+                    let placeheld = Finalizer {
+                        body: Some(body_placeholder(i)),
+                        ..fin.clone()
+                    };
+                    output.push_str(&calculate_finalizer(stc, &placeheld));
+                }
+                _ => output.push_str(&calculate_finalizer(stc, fin)),
+            }
+        }
+        output.push_str("}\n");
+    }
+
+    // the fragments of the definition were checked on their own, check
+    // that they also fit together
+    syn::parse_file(&output).map_err(|e| Error::Syntax(e.to_string()))?;
+    Ok(output)
+}
+
+/// Renders a finalizer method, indented for the final impl block.
+pub fn calculate_finalizer(stc: &Struct, fin: &Finalizer) -> String {
+    let mut s = String::new();
+
+    s.push_str(&format!(
+        "\t{}{}fn {}({})",
+        calculate_visibility(stc, &fin.visibility),
+        if fin.is_async.unwrap_or_default() {
+            "async "
+        } else {
+            ""
+        },
+        fin.name,
+        if fin.by_ref.unwrap_or_default() {
+            "&self"
+        } else {
+            "self"
+        }
+    ));
+
+    if let Some(ref return_type) = fin.return_type {
+        s.push_str(&format!(" -> {}", return_type));
+    }
+    s.push_str(" {\n");
+
+    match fin.body {
+        Some(ref body) => {
+            for line in body.lines() {
+                s.push_str(&format!("\t\t{}\n", line));
+            }
+        }
+        None => s.push_str("\t\ttodo!()\n"),
+    }
+
+    s.push_str("\t}\n\n");
+    s
+}
+
+// stands for the body of the `i`th finalizer while the code is formatted
+fn body_placeholder(i: usize) -> String {
+    format!("__bpb_body!({})", i)
+}
+
+// replaces every placeholder line of `code` with the body of its finalizer,
+// its lines keeping their relative indentation
+fn restore_bodies(code: &str, finalizers: &[Finalizer]) -> String {
+    let mut s = String::with_capacity(code.len());
+    for line in code.lines() {
+        let content = line.trim_start_matches(' ');
+        let body = finalizers
+            .iter()
+            .enumerate()
+            .find(|&(i, _)| content == body_placeholder(i))
+            .and_then(|(_, fin)| fin.body.as_ref());
+        let body = match body {
+            Some(body) => body,
+            None => {
+                s.push_str(line);
+                s.push('\n');
+                continue;
+            }
+        };
+
+        let indent = &line[..line.len() - content.len()];
+        let lines: Vec<&str> = body.lines().map(str::trim_end).collect();
+        let first = lines.iter().position(|l| !l.is_empty()).unwrap_or(0);
+        let last = lines.iter().rposition(|l| !l.is_empty()).unwrap_or(0);
+        let lines = &lines[first..=last];
+        let common = lines
+            .iter()
+            .filter(|l| !l.is_empty())
+            .map(|l| l.len() - l.trim_start_matches([' ', '\t']).len())
+            .min()
+            .unwrap_or(0);
+        for l in lines {
+            if !l.is_empty() {
+                s.push_str(indent);
+                s.push_str(&l[common..]);
+            }
+            s.push('\n');
+        }
+    }
+    s
+}
+
+/// The storage type of a field: `field_type` if it has a default,
+/// `Option<field_type>` otherwise.
+pub fn calculate_type(f: &Field) -> String {
+    match f.initializer() {
+        Some(_) => f.field_type.to_owned(),
+        None => format!("Option<{}>", f.field_type),
+    }
+}
+
+/// The type returned by the getter of a field: `Option<field_type>` for
+/// optional fields without a default, a reference to the collection
+/// for collections not got via clone, `field_type` otherwise.
+pub fn calculate_getter_type(f: &Field) -> String {
+    if f.optional() && f.initializer().is_none() {
+        format!("Option<{}>", f.field_type)
+    } else if f.collection() && !f.get_via_clone.unwrap_or_default() {
+        format!("&{}", f.field_type)
+    } else {
+        f.field_type.to_owned()
+    }
+}
+
+/// The expression returned by the getter of a field, matching
+/// `calculate_getter_type`.
+pub fn calculate_getter_body(f: &Field) -> String {
+    let get_via_clone = f.get_via_clone.unwrap_or_default();
+    if f.collection() && !get_via_clone {
+        format!("&self.{}", f.name)
+    } else if !f.optional() && f.initializer().is_none() {
+        format!(
+            "self.{}{}.unwrap()",
+            f.name,
+            if get_via_clone { ".clone()" } else { "" }
+        )
+    } else {
+        format!(
+            "self.{}{}",
+            f.name,
+            if get_via_clone { ".clone()" } else { "" }
+        )
+    }
+}
+
+/// The `add_` and `extend_` methods of a collection field, for the impl
+/// block callable regardless of the typestates. `add_` adds one item, a
+/// key and a value for maps, and marks a required collection as set;
+/// `extend_` adds every item of an iterator and leaves the typestate
+/// alone, since the iterator may be empty.
+///
+/// `stc` must have passed `validate`.
+pub fn calculate_collection_methods(stc: &Struct, f: &Field) -> String {
+    let (kind, args) = match Collection::of(&f.field_type) {
+        Some(collection) => collection,
+        None => return String::new(),
+    };
+    let inline = if stc.inline() { "#[inline]\n" } else { "" };
+    let visibility = calculate_visibility(stc, &f.visibility);
+
+    let (params, add, item) = match kind {
+        Collection::Vec => (
+            format!("item: {}", args[0]),
+            format!("self.{}.push(item);", f.name),
+            args[0].clone(),
+        ),
+        Collection::HashSet => (
+            format!("item: {}", args[0]),
+            format!("self.{}.insert(item);", f.name),
+            args[0].clone(),
+        ),
+        Collection::HashMap | Collection::BTreeMap => (
+            format!("key: {}, value: {}", args[0], args[1]),
+            format!("self.{}.insert(key, value);", f.name),
+            format!("({}, {})", args[0], args[1]),
+        ),
+    };
+
+    let mut output = String::new();
+    match f.builder_type {
+        Some(ref bt) if !f.optional() => {
+            let bt = [bt.clone()];
+            output.push_str(&format!(
+                "{}\t{}fn add_{}(mut self, {}) -> {}{} {{\n\t\t{}\n",
+                inline,
+                visibility,
+                f.name,
+                params,
+                stc.name,
+                calculate_type_description(stc, &bt, Some(YesNo::Yes)),
+                add
+            ));
+
+            output.push_str(&format!("\t\t{} {{\n", stc.name));
+            for t in stc.constructor_fields.iter() {
+                output.push_str(&format!("\t\t\t{}: self.{},\n", t.name, t.name));
+            }
+            for other in stc.fields.iter().filter(|other| !other.optional()) {
+                output.push_str(&format!("\t\t\tp_{}: PhantomData {{}},\n", other.name));
+            }
+            for other in stc.fields.iter() {
+                output.push_str(&format!("\t\t\t{}: self.{},\n", other.name, other.name));
+            }
+            output.push_str("\t\t}\n\t}\n\n");
+        }
+        _ => output.push_str(&format!(
+            "{}\t{}fn add_{}(mut self, {}) -> Self {{\n\t\t{}\n\t\tself\n\t}}\n\n",
+            inline, visibility, f.name, params, add
+        )),
+    }
+
+    let param = type_param("I", &taken_names(stc));
+    output.push_str(&format!(
+        "{}\t{}fn extend_{}<{}: IntoIterator<Item = {}>>(mut self, items: {}) -> Self {{\n\t\tself.{}.extend(items);\n\t\tself\n\t}}\n\n",
+        inline, visibility, f.name, param, item, param, f.name
+    ));
+
+    output
+}
+
+/// The signature of the setter of a field up to the return type, e.g.
+/// `with_timeout<V: Into<u64>>(self, timeout: V)`. The type parameter of a
+/// generic setter is named so as not to clash with the generic parameters
+/// of `stc`.
+pub fn calculate_setter(stc: &Struct, f: &Field) -> String {
+    setter_signature(f, &taken_names(stc))
+}
+
+/// The statement turning the argument of the setter of a field into the
+/// field type, or an empty string if the setter takes the field type.
+pub fn calculate_setter_conversion(f: &Field) -> String {
+    match f.setter() {
+        Setter::Exact => String::new(),
+        Setter::Into | Setter::ImplTrait => format!(
+            "\t\tlet {}: {} = {}.into();\n",
+            f.name, f.field_type, f.name
+        ),
+        Setter::AsRef => {
+            let (_, referent) = split_reference(&f.field_type).unwrap_or_default();
+            format!(
+                "\t\tlet {}: {} = AsRef::<{}>::as_ref({});\n",
+                f.name, f.field_type, referent, f.name
+            )
+        }
+    }
+}
+
+// the setter signature, with a type parameter named differently from
+// `taken`
+pub(crate) fn setter_signature(f: &Field, taken: &[&str]) -> String {
+    let param = type_param("V", taken);
+
+    match f.setter() {
+        Setter::Exact => format!("with_{}(self, {}: {})", f.name, f.name, f.field_type),
+        Setter::Into => format!(
+            "with_{}<{}: Into<{}>>(self, {}: {})",
+            f.name, param, f.field_type, f.name, param
+        ),
+        Setter::AsRef => {
+            let (reference, referent) = split_reference(&f.field_type).unwrap_or_default();
+            format!(
+                "with_{}<{}: AsRef<{}> + ?Sized>(self, {}: {}{})",
+                f.name, param, referent, f.name, reference, param
+            )
+        }
+        Setter::ImplTrait => format!(
+            "with_{}(self, {}: impl Into<{}>)",
+            f.name, f.name, f.field_type
+        ),
+    }
+}
+
+// the generic parameters of the builder, which the type parameters of its
+// methods must not shadow
+fn taken_names(stc: &Struct) -> Vec<&str> {
+    stc.extra_types
+        .iter()
+        .map(|t| t.name())
+        .chain(stc.fields.iter().filter_map(|f| f.builder_type.as_deref()))
+        .collect()
+}
+
+// `base`, or `base` followed by the first number making it differ from
+// `taken`
+fn type_param(base: &str, taken: &[&str]) -> String {
+    (1..)
+        .map(|i| {
+            if i == 1 {
+                base.to_owned()
+            } else {
+                format!("{}{}", base, i)
+            }
+        })
+        .find(|p| !taken.contains(&p.as_str()))
+        .unwrap()
+}
+
+/// Splits a shared reference type such as `&'a str` into the reference,
+/// `&'a `, and the referenced type, `str`.
+pub(crate) fn split_reference(field_type: &str) -> Option<(String, String)> {
+    let rest = field_type.trim().strip_prefix('&')?.trim_start();
+    let (lifetime, referent) = if rest.starts_with('\'') {
+        let end = rest.find(char::is_whitespace)?;
+        (&rest[..end], rest[end..].trim_start())
+    } else {
+        ("", rest)
+    };
+    if referent.is_empty() || referent.starts_with("mut ") {
+        return None;
+    }
+
+    let reference = if lifetime.is_empty() {
+        "&".to_owned()
+    } else {
+        format!("&{} ", lifetime)
+    };
+    Some((reference, referent.to_owned()))
+}
+
+/// The visibility, followed by a space unless private, of the struct if
+/// `field_visibility` is `None`, or of the getter and setter of a field or
+/// the finalizer with the given `visibility`. Fields and finalizers default
+/// to the visibility of the struct, which defaults to `pub`.
+///
+/// `stc` must have passed `validate`.
+pub fn calculate_visibility(stc: &Struct, field_visibility: &Option<String>) -> String {
+    let visibility = field_visibility
+        .as_ref()
+        .or(stc.visibility.as_ref())
+        .map_or("pub", |v| v.trim());
+
+    if visibility == PRIVATE {
+        String::new()
+    } else {
+        format!("{} ", visibility)
+    }
+}
+
+/// The visibility of `new`, like [`calculate_visibility`]: the visibility
+/// of the struct if it sets one, `pub(crate)` otherwise, as `new` always
+/// was before visibilities could be set.
+pub fn calculate_new_visibility(stc: &Struct) -> String {
+    match stc.visibility {
+        Some(_) => calculate_visibility(stc, &None),
+        None => "pub(crate) ".to_owned(),
+    }
+}
+
+/// The parameter list of `new`, one parameter per constructor field.
+pub fn calculate_constructor_parameters(stc: &Struct) -> String {
+    let mut s = String::new();
+    let mut f_first = true;
+
+    for cp in stc.constructor_fields.iter() {
+        if !f_first {
+            s.push_str(", ");
+        }
+        s.push_str(&format!("{}: {}", cp.name, cp.field_type));
+        f_first = false;
+    }
+    s
+}
+
+/// The generic argument list with every typestate parameter replaced by
+/// the `yes_no` marker.
+pub fn calculate_type_description_all(stc: &Struct, yes_no: YesNo) -> String {
+    Generics::of(stc).render(|_| Some(stc.typestate.marker(yes_no)))
+}
+
+/// The generic argument list naming the builder type. Typestate parameters
+/// whose builder type is in `builders_type_to_skip` are replaced with the
+/// `replace_with` marker, or omitted if it is `None`.
+pub fn calculate_type_description(
+    stc: &Struct,
+    builders_type_to_skip: &[String],
+    replace_with: Option<YesNo>,
+) -> String {
+    Generics::of(stc).render(|bt| {
+        if builders_type_to_skip.iter().any(|skip| skip == bt) {
+            replace_with.map(|yes_no| stc.typestate.marker(yes_no))
+        } else {
+            Some(bt)
+        }
+    })
+}
+
+/// The generic parameter list declaring a struct or impl, without the
+/// typestate parameters whose builder type is in `builders_type_to_skip`.
+/// The bounds of the extra types are included unless `bounds_in_where` is
+/// set.
+pub fn calculate_generic_params(stc: &Struct, builders_type_to_skip: &[String]) -> String {
+    Generics::of(stc).render_params(
+        |bt| {
+            if builders_type_to_skip.iter().any(|skip| skip == bt) {
+                None
+            } else {
+                Some(bt)
+            }
+        },
+        !stc.bounds_in_where(),
+    )
+}
+
+/// The `where` clause, bounding every typestate parameter not in
+/// `builders_type_to_skip` by `ToAssign`, followed by the bounds of the
+/// extra types if `bounds_in_where` is set and by `extra_wheres`.
+///
+/// `stc` must have passed `validate`.
+pub fn calculate_where(stc: &Struct, builders_type_to_skip: &[String]) -> String {
+    let mut s = String::new();
+
+    for f in stc.fields.iter().filter(|f| !f.optional()).filter(|f| {
+        let bt = f.builder_type.clone().unwrap();
+        !builders_type_to_skip.contains(&bt)
+    }) {
+        s.push_str(&format!(
+            "\t{} : ToAssign,\n",
+            f.clone().builder_type.unwrap()
+        ));
+    }
+
+    if stc.bounds_in_where() {
+        for predicate in stc.extra_types.iter().filter_map(ExtraType::predicate) {
+            s.push_str(&format!("\t{},\n", predicate));
+        }
+    }
+
+    for ew in stc.extra_wheres.iter() {
+        s.push_str(&format!("\t{},\n", ew));
+    }
+
+    if s.is_empty() {
+        "".to_owned()
+    } else {
+        format!("where\n{}", s)
+    }
+}
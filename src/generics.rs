@@ -0,0 +1,87 @@
+use model::{ExtraType, Struct};
+
+/// The generic parameters of a builder, in the order Rust requires them:
+/// the lifetimes, then the type and const parameters, all from
+/// `extra_types`, then one typestate parameter per required field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Generics {
+    pub lifetimes: Vec<ExtraType>,
+    /// The type and const parameters, in declaration order.
+    pub types: Vec<ExtraType>,
+    /// The `builder_type` of each required field, in field order.
+    pub typestates: Vec<String>,
+}
+
+impl Generics {
+    /// Collects the generic parameters of `stc`. Required fields without a
+    /// `builder_type`, which `validate` rejects, are left out.
+    pub fn of(stc: &Struct) -> Generics {
+        let (lifetimes, types) = stc
+            .extra_types
+            .iter()
+            .cloned()
+            .partition(ExtraType::is_lifetime);
+
+        Generics {
+            lifetimes,
+            types,
+            typestates: stc
+                .fields
+                .iter()
+                .filter(|f| !f.optional())
+                .filter_map(|f| f.builder_type.clone())
+                .collect(),
+        }
+    }
+
+    /// Renders the generic arguments naming the builder type, between angle
+    /// brackets, or nothing if there are none. Each typestate parameter is
+    /// passed to `typestate`, which returns what to render in its place, or
+    /// `None` to leave it out.
+    pub fn render<'a, F>(&'a self, typestate: F) -> String
+    where
+        F: FnMut(&'a str) -> Option<&'a str>,
+    {
+        let extra = self
+            .lifetimes
+            .iter()
+            .chain(self.types.iter())
+            .map(|t| t.name().to_owned());
+        angle_brackets(extra.chain(self.typestates(typestate)).collect())
+    }
+
+    /// Renders the generic parameters of a struct or impl declaration, like
+    /// [`Generics::render`] but declaring the const parameters and, if
+    /// `with_bounds` is set, the bounds of the extra types.
+    pub fn render_params<'a, F>(&'a self, typestate: F, with_bounds: bool) -> String
+    where
+        F: FnMut(&'a str) -> Option<&'a str>,
+    {
+        let extra = self
+            .lifetimes
+            .iter()
+            .chain(self.types.iter())
+            .map(|t| t.declaration(with_bounds));
+        angle_brackets(extra.chain(self.typestates(typestate)).collect())
+    }
+
+    fn typestates<'a, F>(&'a self, typestate: F) -> Vec<String>
+    where
+        F: FnMut(&'a str) -> Option<&'a str>,
+    {
+        self.typestates
+            .iter()
+            .map(String::as_str)
+            .filter_map(typestate)
+            .map(str::to_owned)
+            .collect()
+    }
+}
+
+fn angle_brackets(params: Vec<String>) -> String {
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", params.join(", "))
+    }
+}
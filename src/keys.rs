@@ -0,0 +1,176 @@
+use error::Diagnostic;
+use locate::Path;
+use model::{Catalog, CatalogEntry, ConstructorField, Field, Finalizer, Naming, Struct, Typestate};
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde_json::{Map, Value};
+
+/// The kinds of files read by bpb, which decide the keys allowed where.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Document {
+    Definition,
+    Catalog,
+    Typestate,
+}
+
+// the keys of an `extra_types` entry written as an object
+const EXTRA_TYPE_KEYS: &[&str] = &["lifetime", "type", "const", "bounds"];
+
+/// Reports every key of `value` that the model does not know, with the
+/// closest known key if there is one close enough to be a typo.
+pub(crate) fn unknown_keys(value: &Value, document: Document) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let root = Path::default();
+
+    match document {
+        Document::Definition => definition(&mut diagnostics, value, &root),
+        Document::Catalog => {
+            if let Some(object) = check(&mut diagnostics, value, &root, keys::<Catalog>()) {
+                if let Some(Value::Object(entries)) = object.get("fields") {
+                    for (id, entry) in entries.iter() {
+                        let path = root.key("fields").key(id);
+                        if let Some(entry) =
+                            check(&mut diagnostics, entry, &path, keys::<CatalogEntry>())
+                        {
+                            extra_types(&mut diagnostics, entry, &path);
+                        }
+                    }
+                }
+            }
+        }
+        Document::Typestate => {
+            check(&mut diagnostics, value, &root, keys::<Typestate>());
+        }
+    }
+
+    diagnostics
+}
+
+fn definition(diagnostics: &mut Vec<Diagnostic>, value: &Value, path: &Path) {
+    let object = match check(diagnostics, value, path, keys::<Struct>()) {
+        Some(object) => object,
+        None => return,
+    };
+
+    extra_types(diagnostics, object, path);
+    if let Some(typestate) = object.get("typestate") {
+        check(
+            diagnostics,
+            typestate,
+            &path.key("typestate"),
+            keys::<Typestate>(),
+        );
+    }
+    if let Some(naming) = object.get("naming") {
+        check(diagnostics, naming, &path.key("naming"), keys::<Naming>());
+    }
+
+    let lists = [
+        ("constructor_fields", keys::<ConstructorField>()),
+        ("fields", keys::<Field>()),
+        ("finalizers", keys::<Finalizer>()),
+    ];
+    for &(key, known) in lists.iter() {
+        if let Some(Value::Array(items)) = object.get(key) {
+            for (i, item) in items.iter().enumerate() {
+                check(diagnostics, item, &path.key(key).index(i), known);
+            }
+        }
+    }
+}
+
+fn extra_types(diagnostics: &mut Vec<Diagnostic>, object: &Map<String, Value>, path: &Path) {
+    if let Some(Value::Array(items)) = object.get("extra_types") {
+        for (i, item) in items.iter().enumerate() {
+            if item.is_object() {
+                check(
+                    diagnostics,
+                    item,
+                    &path.key("extra_types").index(i),
+                    EXTRA_TYPE_KEYS,
+                );
+            }
+        }
+    }
+}
+
+// reports the keys of `value` missing from `known`, returning the object
+// to look into further
+fn check<'a>(
+    diagnostics: &mut Vec<Diagnostic>,
+    value: &'a Value,
+    path: &Path,
+    known: &[&str],
+) -> Option<&'a Map<String, Value>> {
+    let object = value.as_object()?;
+    for key in object.keys() {
+        if known.contains(&key.as_str()) {
+            continue;
+        }
+        let message = match suggest(key, known) {
+            Some(suggestion) => format!("unknown key `{}`, did you mean `{}`?", key, suggestion),
+            None => format!("unknown key `{}`", key),
+        };
+        diagnostics.push(Diagnostic::new(path.key(key), message));
+    }
+    Some(object)
+}
+
+// the known key closest to `key`, if it is a plausible typo
+fn suggest<'a>(key: &str, known: &[&'a str]) -> Option<&'a str> {
+    let limit = (key.chars().count() / 3).max(1);
+    known
+        .iter()
+        .map(|k| (edit_distance(key, k), *k))
+        .filter(|&(distance, _)| distance <= limit)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, k)| k)
+}
+
+// the Levenshtein distance between `a` and `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == *cb { 0 } else { 1 };
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+// the keys `T` accepts, as listed by serde_derive when it asks for a struct
+fn keys<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    let mut keys: &'static [&'static str] = &[];
+    let _ = T::deserialize(KeysOf(&mut keys));
+    keys
+}
+
+// a deserializer recording the fields of the struct it is asked for
+struct KeysOf<'a>(&'a mut &'static [&'static str]);
+
+impl<'de, 'a> Deserializer<'de> for KeysOf<'a> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        _: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(de::Error::custom("only the keys are wanted"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
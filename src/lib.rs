@@ -0,0 +1,83 @@
+//! Generates typestate builders from a `Struct` definition.
+//!
+//! The usual entry points are [`load`] to read a definition file and
+//! [`generate`] to turn it into Rust source code. The `calculate_*` helpers
+//! used by `generate` are exposed as well, for callers that assemble their
+//! own output. Build scripts can use [`build::generate_dir`]. Definitions
+//! can be written in JSON, JSON5, TOML, YAML or RON, see [`Syntax`]; their
+//! JSON Schema is returned by [`schema`].
+//!
+//! Field definitions shared by several builders can be kept in a
+//! [`Catalog`], read with [`load_catalog`]; the fields of a builder refer to
+//! its entries with `"use": "<id>"` and are resolved by
+//! [`load_all_with_catalog`]. [`generate_catalog`] emits the catalog traits
+//! once, for a module shared by the builders. A builder with a [`Naming`]
+//! convention can leave out the typestate and trait names of its fields,
+//! which [`apply_naming`] derives from the field names.
+//!
+//! The `ToAssign` trait and the `Yes` and `No` typestate markers, whose
+//! names and shape a [`Typestate`] configures, can be generated once with
+//! [`generate_prelude`]; builders with a `prelude_path` import them from
+//! there.
+
+extern crate json5;
+extern crate prettyplease;
+extern crate ron;
+extern crate schemars;
+extern crate serde;
+extern crate serde_json;
+extern crate serde_yaml;
+extern crate similar;
+extern crate syn;
+extern crate toml;
+
+#[macro_use]
+extern crate serde_derive;
+
+pub mod build;
+mod catalog;
+mod error;
+mod format;
+mod generate;
+mod generics;
+mod keys;
+mod load;
+mod locate;
+mod model;
+mod naming;
+mod output;
+mod prelude;
+mod schema;
+mod syntax;
+mod traits;
+mod validate;
+
+pub use catalog::resolve;
+pub use error::{Diagnostic, Error};
+pub use generate::{
+    calculate_collection_methods, calculate_constructor_parameters, calculate_finalizer,
+    calculate_generic_params, calculate_getter_body, calculate_getter_type,
+    calculate_new_visibility, calculate_setter, calculate_setter_conversion, calculate_type,
+    calculate_type_description, calculate_type_description_all, calculate_visibility,
+    calculate_where, generate, generate_catalog, generate_prelude, generate_unformatted,
+};
+pub use generics::Generics;
+pub use load::{
+    catalog_from_str, from_str, from_str_all, from_str_lines, from_str_syntax, load, load_all,
+    load_all_lenient, load_all_with_catalog, load_catalog, load_catalog_lenient, load_typestate,
+    load_typestate_lenient, typestate_from_str,
+};
+pub use locate::{Location, Path, Segment};
+pub use model::{
+    Catalog, CatalogEntry, Collection, ConstructorField, ExtraType, Field, Finalizer, Naming,
+    Setter, Struct, Typestate, YesNo, PRIVATE,
+};
+pub use naming::{apply_naming, camel_case, snake_case};
+pub use output::{check_file, check_overwritable, is_generated, render, write_file, MARKER};
+pub use prelude::{calculate_prelude, calculate_uses};
+pub use schema::schema;
+pub use syntax::Syntax;
+pub use traits::{
+    calculate_catalog_traits, calculate_traits, collect_catalog_traits, collect_traits, TraitDef,
+};
+pub use validate::{validate, validate_typestate};
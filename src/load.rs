@@ -0,0 +1,437 @@
+use catalog::resolve;
+use error::{Diagnostic, Error};
+use keys::{unknown_keys, Document};
+use locate::{without_position, Location, Path};
+use model::{Catalog, Struct, Typestate};
+use naming::apply_naming;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::Path as FilePath;
+use syntax::Syntax;
+use traits::collect_catalog_traits;
+use validate::{validate, validate_typestate};
+
+/// Parses and validates a single JSON definition. Validation problems are
+/// reported with their position in `text`.
+pub fn from_str(text: &str) -> Result<Struct, Error> {
+    parse(text, Syntax::Json, Options::default())
+}
+
+/// Parses and validates every definition in `text`, which holds either a
+/// single `Struct` or an array of them.
+pub fn from_str_all(text: &str) -> Result<Vec<Struct>, Error> {
+    parse_all(text, Syntax::Json, Options::default())
+}
+
+/// Parses and validates a JSON Lines text, one `Struct` per line. Blank
+/// lines are skipped; paths in diagnostics start with the index of the
+/// definition.
+pub fn from_str_lines(text: &str) -> Result<Vec<Struct>, Error> {
+    parse_lines(text, Options::default())
+}
+
+/// Parses and validates every definition in `text`, written in `syntax`.
+/// Validation problems are reported with their position in `text`.
+pub fn from_str_syntax(text: &str, syntax: Syntax) -> Result<Vec<Struct>, Error> {
+    parse_any(text, syntax, Options::default())
+}
+
+/// Parses and checks a catalog of shared field definitions. Problems with
+/// the traits of its entries are reported with their position in `text`.
+pub fn catalog_from_str(text: &str) -> Result<Catalog, Error> {
+    parse_catalog(text, Syntax::Json, false)
+}
+
+/// Parses and validates a typestate configuration shared by several
+/// builders.
+pub fn typestate_from_str(text: &str) -> Result<Typestate, Error> {
+    parse_typestate(text, Syntax::Json, false)
+}
+
+/// Reads, parses and validates the definition stored in `path`, in the
+/// syntax chosen by its extension, see [`Syntax::from_path`]. Files with
+/// any other extension are read as JSON.
+///
+/// Keys the definition does not know are reported as errors, like every
+/// other function reading a file or a text; the `_lenient` variants ignore
+/// them instead.
+pub fn load<P: AsRef<FilePath>>(path: P) -> Result<Struct, Error> {
+    let path = path.as_ref();
+    parse(&read(path)?, syntax_of(path), Options::default())
+}
+
+/// Reads, parses and validates every definition stored in `path`. Files
+/// with the `.jsonl` extension are read as JSON Lines, everything else as
+/// a single definition or an array of definitions, in the syntax chosen by
+/// the extension.
+pub fn load_all<P: AsRef<FilePath>>(path: P) -> Result<Vec<Struct>, Error> {
+    read_all(path.as_ref(), Options::default())
+}
+
+/// Like [`load_all`], but resolves the `use` references of the fields
+/// against `catalog` before validating.
+pub fn load_all_with_catalog<P: AsRef<FilePath>>(
+    path: P,
+    catalog: &Catalog,
+) -> Result<Vec<Struct>, Error> {
+    read_all(
+        path.as_ref(),
+        Options {
+            catalog: Some(catalog),
+            lenient: false,
+        },
+    )
+}
+
+/// Like [`load_all`] or [`load_all_with_catalog`], but ignores unknown
+/// keys, for definitions written before they were rejected.
+pub fn load_all_lenient<P: AsRef<FilePath>>(
+    path: P,
+    catalog: Option<&Catalog>,
+) -> Result<Vec<Struct>, Error> {
+    read_all(
+        path.as_ref(),
+        Options {
+            catalog,
+            lenient: true,
+        },
+    )
+}
+
+/// Reads and checks the catalog stored in `path`.
+pub fn load_catalog<P: AsRef<FilePath>>(path: P) -> Result<Catalog, Error> {
+    let path = path.as_ref();
+    parse_catalog(&read(path)?, syntax_of(path), false)
+}
+
+/// Like [`load_catalog`], but ignores unknown keys.
+pub fn load_catalog_lenient<P: AsRef<FilePath>>(path: P) -> Result<Catalog, Error> {
+    let path = path.as_ref();
+    parse_catalog(&read(path)?, syntax_of(path), true)
+}
+
+/// Reads and validates the typestate configuration stored in `path`.
+pub fn load_typestate<P: AsRef<FilePath>>(path: P) -> Result<Typestate, Error> {
+    let path = path.as_ref();
+    parse_typestate(&read(path)?, syntax_of(path), false)
+}
+
+/// Like [`load_typestate`], but ignores unknown keys.
+pub fn load_typestate_lenient<P: AsRef<FilePath>>(path: P) -> Result<Typestate, Error> {
+    let path = path.as_ref();
+    parse_typestate(&read(path)?, syntax_of(path), true)
+}
+
+// how definitions are read
+#[derive(Clone, Copy, Default)]
+struct Options<'a> {
+    // resolves the `use` references of the fields
+    catalog: Option<&'a Catalog>,
+    // ignores unknown keys
+    lenient: bool,
+}
+
+// the text a definition was parsed from, where its diagnostics are located
+#[derive(Clone, Copy)]
+struct Source<'t> {
+    text: &'t str,
+    syntax: Syntax,
+    // the number of lines preceding `text` in the file
+    line_offset: usize,
+}
+
+impl<'t> Source<'t> {
+    fn new(text: &'t str, syntax: Syntax) -> Source<'t> {
+        Source {
+            text,
+            syntax,
+            line_offset: 0,
+        }
+    }
+}
+
+fn syntax_of(path: &FilePath) -> Syntax {
+    Syntax::from_path(path).unwrap_or(Syntax::Json)
+}
+
+// the unknown keys of `text`, none if `lenient`
+fn unknown_in(
+    text: &str,
+    syntax: Syntax,
+    document: Document,
+    lenient: bool,
+) -> Result<Vec<Diagnostic>, Error> {
+    if lenient {
+        return Ok(Vec::new());
+    }
+    Ok(unknown_keys(&syntax.to_value(text)?, document))
+}
+
+// deserializes `text`, blaming a failure on the `unknown` keys if there are
+// any, since a misspelled key is the likely cause of a missing field
+fn deserialize<T: DeserializeOwned>(
+    text: &str,
+    syntax: Syntax,
+    unknown: &[Diagnostic],
+) -> Result<T, Error> {
+    syntax.deserialize(text).map_err(|e| {
+        if unknown.is_empty() {
+            e
+        } else {
+            Error::Invalid(locate_all(
+                Source::new(text, syntax),
+                &Path::default(),
+                &Path::default(),
+                unknown.to_vec(),
+            ))
+        }
+    })
+}
+
+fn parse_catalog(text: &str, syntax: Syntax, lenient: bool) -> Result<Catalog, Error> {
+    let mut diagnostics = unknown_in(text, syntax, Document::Catalog, lenient)?;
+    let catalog: Catalog = deserialize(text, syntax, &diagnostics)?;
+    if let Err(mut traits) = collect_catalog_traits(&catalog) {
+        diagnostics.append(&mut traits);
+    }
+    if diagnostics.is_empty() {
+        return Ok(catalog);
+    }
+
+    Err(Error::Invalid(locate_all(
+        Source::new(text, syntax),
+        &Path::default(),
+        &Path::default(),
+        diagnostics,
+    )))
+}
+
+fn parse_typestate(text: &str, syntax: Syntax, lenient: bool) -> Result<Typestate, Error> {
+    let mut diagnostics = unknown_in(text, syntax, Document::Typestate, lenient)?;
+    let typestate: Typestate = deserialize(text, syntax, &diagnostics)?;
+    diagnostics.append(&mut validate_typestate(&typestate, Path::default()));
+    if diagnostics.is_empty() {
+        return Ok(typestate);
+    }
+
+    Err(Error::Invalid(locate_all(
+        Source::new(text, syntax),
+        &Path::default(),
+        &Path::default(),
+        diagnostics,
+    )))
+}
+
+fn parse(text: &str, syntax: Syntax, options: Options) -> Result<Struct, Error> {
+    let unknown = unknown_in(text, syntax, Document::Definition, options.lenient)?;
+    let stc: Struct = deserialize(text, syntax, &unknown)?;
+    let root = Path::default();
+    check(
+        Source::new(text, syntax),
+        &root,
+        &root,
+        options,
+        stc,
+        unknown,
+    )
+}
+
+fn parse_any(text: &str, syntax: Syntax, options: Options) -> Result<Vec<Struct>, Error> {
+    match syntax {
+        Syntax::JsonLines => parse_lines(text, options),
+        _ => parse_all(text, syntax, options),
+    }
+}
+
+fn parse_all(text: &str, syntax: Syntax, options: Options) -> Result<Vec<Struct>, Error> {
+    if syntax.is_json() && !text.trim_start().starts_with('[') {
+        return parse(text, syntax, options).map(|stc| vec![stc]);
+    }
+
+    let values = match syntax.to_value(text)? {
+        Value::Array(values) => values,
+        _ => return parse(text, syntax, options).map(|stc| vec![stc]),
+    };
+    let unknown = values
+        .iter()
+        .map(|value| {
+            if options.lenient {
+                Vec::new()
+            } else {
+                unknown_keys(value, Document::Definition)
+            }
+        })
+        .collect::<Vec<_>>();
+    let structs: Vec<Struct> = syntax.deserialize(text).map_err(|e| {
+        if unknown.iter().all(Vec::is_empty) {
+            return e;
+        }
+        Error::Invalid(
+            unknown
+                .iter()
+                .enumerate()
+                .flat_map(|(i, unknown)| {
+                    let path = Path::default().index(i);
+                    locate_all(Source::new(text, syntax), &path, &path, unknown.clone())
+                })
+                .collect(),
+        )
+    })?;
+
+    check_all(
+        structs
+            .into_iter()
+            .zip(unknown)
+            .enumerate()
+            .map(|(i, (stc, unknown))| {
+                let path = Path::default().index(i);
+                check(
+                    Source::new(text, syntax),
+                    &path,
+                    &path,
+                    options,
+                    stc,
+                    unknown,
+                )
+            }),
+    )
+}
+
+fn parse_lines(text: &str, options: Options) -> Result<Vec<Struct>, Error> {
+    check_all(
+        text.lines()
+            .enumerate()
+            .filter(|&(_, line)| !line.trim().is_empty())
+            .enumerate()
+            .map(|(i, (line_no, line))| {
+                let path = Path::default().index(i);
+                let source = Source {
+                    text: line,
+                    syntax: Syntax::JsonLines,
+                    line_offset: line_no,
+                };
+                let unknown = match serde_json::from_str(line) {
+                    Ok(value) if !options.lenient => unknown_keys(&value, Document::Definition),
+                    Ok(_) => Vec::new(),
+                    Err(e) => {
+                        return Err(Error::Invalid(vec![parse_diagnostic(path, line_no, &e)]))
+                    }
+                };
+                match serde_json::from_str(line) {
+                    Ok(stc) => check(source, &Path::default(), &path, options, stc, unknown),
+                    // a misspelled key is the likely cause of a missing field
+                    Err(_) if !unknown.is_empty() => Err(Error::Invalid(locate_all(
+                        source,
+                        &Path::default(),
+                        &path,
+                        unknown,
+                    ))),
+                    Err(e) => Err(Error::Invalid(vec![parse_diagnostic(path, line_no, &e)])),
+                }
+            }),
+    )
+}
+
+fn read_all(path: &FilePath, options: Options) -> Result<Vec<Struct>, Error> {
+    parse_any(&read(path)?, syntax_of(path), options)
+}
+
+fn read(path: &FilePath) -> Result<String, Error> {
+    let mut f = File::open(path)?;
+    let mut contents = String::new();
+    f.read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+// resolves against the catalog of `options`, if any, applies the naming
+// convention and validates a parsed definition, reporting its `unknown`
+// keys along. `source` is the text it was parsed from, where the
+// definition is found at `in_text`; `prefix` is prepended to the reported
+// paths
+fn check(
+    source: Source,
+    in_text: &Path,
+    prefix: &Path,
+    options: Options,
+    mut stc: Struct,
+    mut diagnostics: Vec<Diagnostic>,
+) -> Result<Struct, Error> {
+    let mut resolved = match options.catalog {
+        Some(catalog) => resolve(&mut stc, catalog),
+        None => Vec::new(),
+    };
+    apply_naming(&mut stc);
+    if resolved.is_empty() {
+        diagnostics.append(&mut validate(&stc));
+    } else {
+        diagnostics.append(&mut resolved);
+    }
+    if diagnostics.is_empty() {
+        return Ok(stc);
+    }
+
+    Err(Error::Invalid(locate_all(
+        source,
+        in_text,
+        prefix,
+        diagnostics,
+    )))
+}
+
+// fills in the location of each diagnostic and prefixes its path, see
+// `check`
+fn locate_all(
+    source: Source,
+    in_text: &Path,
+    prefix: &Path,
+    mut diagnostics: Vec<Diagnostic>,
+) -> Vec<Diagnostic> {
+    for d in diagnostics.iter_mut() {
+        let relative = d.path.0.drain(..).collect::<Vec<_>>();
+
+        let mut path = in_text.clone();
+        path.0.extend(relative.iter().cloned());
+        d.location = source.syntax.locate(source.text, &path).map(|l| Location {
+            line: l.line + source.line_offset,
+            column: l.column,
+        });
+
+        let mut path = prefix.clone();
+        path.0.extend(relative);
+        d.path = path;
+    }
+    diagnostics
+}
+
+// collects every definition, merging the diagnostics of all the invalid ones
+fn check_all<I: Iterator<Item = Result<Struct, Error>>>(results: I) -> Result<Vec<Struct>, Error> {
+    let mut structs = Vec::new();
+    let mut diagnostics = Vec::new();
+
+    for result in results {
+        match result {
+            Ok(stc) => structs.push(stc),
+            Err(Error::Invalid(mut d)) => diagnostics.append(&mut d),
+            Err(e) => return Err(e),
+        }
+    }
+
+    if diagnostics.is_empty() {
+        Ok(structs)
+    } else {
+        Err(Error::Invalid(diagnostics))
+    }
+}
+
+fn parse_diagnostic(path: Path, line_offset: usize, e: &serde_json::Error) -> Diagnostic {
+    Diagnostic {
+        path,
+        message: without_position(&e.to_string()),
+        location: Some(Location {
+            line: e.line() + line_offset,
+            column: e.column(),
+        }),
+    }
+}
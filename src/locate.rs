@@ -0,0 +1,217 @@
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use std::fmt;
+
+/// One step of a path into a definition, either an object key or an
+/// array index.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// A path into a definition, printed as `fields[3].builder_type`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Path(pub Vec<Segment>);
+
+/// One-based line and column in the source text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Path {
+    pub fn key(&self, key: &str) -> Path {
+        let mut p = self.clone();
+        p.0.push(Segment::Key(key.to_owned()));
+        p
+    }
+
+    pub fn index(&self, index: usize) -> Path {
+        let mut p = self.clone();
+        p.0.push(Segment::Index(index));
+        p
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "<root>");
+        }
+
+        for (i, s) in self.0.iter().enumerate() {
+            match *s {
+                Segment::Key(ref k) if i == 0 => write!(f, "{}", k)?,
+                Segment::Key(ref k) => write!(f, ".{}", k)?,
+                Segment::Index(idx) => write!(f, "[{}]", idx)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Removes the ` at line L column C` serde_json and serde_yaml append to
+/// their messages, for the diagnostics that report the location on their
+/// own.
+pub(crate) fn without_position(message: &str) -> String {
+    match message.rfind(" at line ") {
+        Some(pos) => message[..pos].to_owned(),
+        None => message.to_owned(),
+    }
+}
+
+/// The message of the error a [`Probe`] stops with once it reached its
+/// path.
+pub(crate) const FOUND: &str = "bpb: path found";
+
+/// Finds a path in any syntax serde can read: deserialized with a parser,
+/// the probe stops with a [`FOUND`] error as soon as it reaches the path,
+/// which the parser reports at the position of the key or value it was
+/// reading. Deserializing succeeds, or fails with another error, if the
+/// path does not exist.
+pub(crate) struct Probe<'p>(pub &'p [Segment]);
+
+// reads a key of a map: stops if it is the last segment of the path,
+// otherwise tells whether it is the key followed by the rest of the path
+struct KeyProbe<'p> {
+    key: &'p str,
+    last: bool,
+}
+
+impl<'de, 'p> DeserializeSeed<'de> for Probe<'p> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'p> Probe<'p> {
+    fn scalar<E: de::Error>(self) -> Result<(), E> {
+        if self.0.is_empty() {
+            Err(E::custom(FOUND))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<'de, 'p> Visitor<'de> for Probe<'p> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "any value")
+    }
+
+    fn visit_bool<E: de::Error>(self, _: bool) -> Result<(), E> {
+        self.scalar()
+    }
+
+    fn visit_i64<E: de::Error>(self, _: i64) -> Result<(), E> {
+        self.scalar()
+    }
+
+    fn visit_u64<E: de::Error>(self, _: u64) -> Result<(), E> {
+        self.scalar()
+    }
+
+    fn visit_f64<E: de::Error>(self, _: f64) -> Result<(), E> {
+        self.scalar()
+    }
+
+    fn visit_str<E: de::Error>(self, _: &str) -> Result<(), E> {
+        self.scalar()
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<(), E> {
+        self.scalar()
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<(), E> {
+        self.scalar()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let index = match self.0.split_first() {
+            None => return Err(de::Error::custom(FOUND)),
+            Some((Segment::Index(index), _)) => *index,
+            Some(_) => return Ok(()),
+        };
+        for _ in 0..index {
+            if seq.next_element::<IgnoredAny>()?.is_none() {
+                return Ok(());
+            }
+        }
+        seq.next_element_seed(Probe(&self.0[1..]))?;
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let key = match self.0.split_first() {
+            None => return Err(de::Error::custom(FOUND)),
+            Some((Segment::Key(key), _)) => key,
+            Some(_) => return Ok(()),
+        };
+        let rest = &self.0[1..];
+        let probe = KeyProbe {
+            key,
+            last: rest.is_empty(),
+        };
+        while let Some(found) = map.next_key_seed(&probe)? {
+            if found {
+                return map.next_value_seed(Probe(rest));
+            }
+            map.next_value::<IgnoredAny>()?;
+        }
+        Ok(())
+    }
+}
+
+impl<'de, 'a, 'p> DeserializeSeed<'de> for &'a KeyProbe<'p> {
+    type Value = bool;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<bool, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de, 'a, 'p> Visitor<'de> for &'a KeyProbe<'p> {
+    type Value = bool;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a key")
+    }
+
+    fn visit_str<E: de::Error>(self, key: &str) -> Result<bool, E> {
+        match key == self.key {
+            true if self.last => Err(E::custom(FOUND)),
+            found => Ok(found),
+        }
+    }
+}
+
+/// The one-based line and column of the byte offset `pos` in `text`.
+pub(crate) fn location_of(text: &str, pos: usize) -> Location {
+    let before = &text[..pos];
+    let line = before.matches('\n').count() + 1;
+    let column = match before.rfind('\n') {
+        Some(nl) => before[nl + 1..].chars().count() + 1,
+        None => before.chars().count() + 1,
+    };
+    Location { line, column }
+}
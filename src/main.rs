@@ -1,725 +1,284 @@
-extern crate serde;
-extern crate serde_json;
-
-#[macro_use]
-extern crate serde_derive;
-
-use std::fs::File;
-use std::io::prelude::*;
-
-#[derive(Debug, Clone, Deserialize)]
-pub struct Field {
-    pub name: String,
-    pub field_type: String,
-    pub builder_type: Option<String>,
-    pub optional: bool,
-    pub initializer: Option<String>,
-    pub trait_get: Option<String>,
-    pub trait_set: Option<String>,
-    pub get_via_clone: Option<bool>,
-}
-
-#[derive(Debug, Clone, Deserialize)]
-pub struct ConstructorField {
-    pub name: String,
-    pub field_type: String,
-    pub trait_get: Option<String>,
-    pub get_via_clone: Option<bool>,
-}
-
-#[derive(Debug, Clone, Deserialize)]
-pub struct Struct {
-    pub name: String,
-    pub derive: Option<String>,
-    pub uses: Vec<String>,
-    pub inline: Option<bool>,
-    pub extra_types: Vec<String>,
-    pub extra_wheres: Vec<String>,
-    pub prepend_required_types: Option<bool>,
-    pub constructor_fields: Vec<ConstructorField>,
-    pub fields: Vec<Field>,
+extern crate bpb;
+
+use bpb::Error;
+use std::fs;
+use std::path::PathBuf;
+
+const USAGE: &str = "usage: bpb [--out FILE | --out-dir DIR] [--force] [CATALOG] <definition>...
+       bpb --check (--against FILE | --out-dir DIR) [CATALOG] <definition>...
+       bpb [--check] [--force] --emit-prelude FILE [<definition>...]
+       bpb schema
+
+Every form also accepts --typestate FILE and --lenient.
+
+CATALOG: --catalog FILE [--catalog-traits FILE]
+
+Each definition file holds a single builder, an array of builders or, with
+the .jsonl extension, one builder per line. Definitions, catalogs and
+typestate configurations are read as JSON5 (.json5, .jsonc), TOML (.toml),
+YAML (.yaml, .yml), RON (.ron) or JSON, depending on the extension. The
+generated builders are printed one after the other, or written to FILE with
+--out. With --out-dir each builder is written to its own file named after
+the builder in snake case. Nothing is written if two builders would go to
+the same file.
+
+Files are only overwritten if they were previously generated by bpb, unless
+--force is given; if one of them was not, nothing is written.
+
+With --check nothing is written: the builders are regenerated in memory and
+compared with FILE, or with the files in DIR. Any difference is printed as a
+unified diff and bpb exits with a non-zero status.
+
+With --catalog, fields can take their definition from an entry of the
+catalog FILE with \"use\": \"<id>\". --catalog-traits also generates the
+catalog traits into their own FILE, checked like the builders with --check.
+
+--emit-prelude writes the typestate types ToAssign, Yes and No to FILE, for
+the builders whose prelude_path names its module.
+
+--typestate reads a typestate configuration from FILE, used by the prelude
+and by every builder for the settings its own typestate leaves out.
+
+Unknown keys in definitions, catalogs and typestate configurations are
+errors, reported with the closest known key. --lenient ignores them, for
+files written before they were rejected.
+
+bpb schema prints the JSON Schema of definition files, for editors to
+complete and check them. Catalogs and typestate configurations are not
+covered by it.";
+
+struct Options {
+    inputs: Vec<String>,
+    out: Option<PathBuf>,
+    out_dir: Option<PathBuf>,
+    force: bool,
+    check: bool,
+    catalog: Option<PathBuf>,
+    catalog_traits: Option<PathBuf>,
+    prelude: Option<PathBuf>,
+    typestate: Option<PathBuf>,
+    lenient: bool,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum YesNo {
-    Yes,
-    No,
-}
-
-impl Struct {
-    pub fn inline(&self) -> bool {
-        if let Some(i) = self.inline {
-            i
-        } else {
-            false
-        }
-    }
-}
-
-fn main() {
-    let file_name = std::env::args()
-        .nth(1)
-        .expect("pass the json name as parameter");
-
-    let stc: Struct = {
-        let mut f = File::open(file_name).expect("file not found!");
-        let mut contents = String::new();
-        f.read_to_string(&mut contents).unwrap();
-        serde_json::from_str(&contents).unwrap()
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        inputs: Vec::new(),
+        out: None,
+        out_dir: None,
+        force: false,
+        check: false,
+        catalog: None,
+        catalog_traits: None,
+        prelude: None,
+        typestate: None,
+        lenient: false,
     };
 
-    let all_builder_types = {
-        let mut abt = Vec::new();
-        stc.fields
-            .iter()
-            .filter(|f| !f.optional)
-            .for_each(|bt| abt.push(bt.builder_type.clone().unwrap()));
-        abt
-    };
-
-    let mut output = String::new();
-
-    let mut regardless = String::new();
-
-    // dump uses
-    if !stc.uses.is_empty() {
-        stc.uses.iter().for_each(|u| {
-            if u.chars().last().unwrap() == ';' {
-                output.push_str(&format!("use {}\n", u))
-            } else {
-                output.push_str(&format!("use {};\n", u))
-            }
-        });
-
-        output.push_str("\n");
-    }
-
-    // prepend mandatory types if required
-    if let Some(prepend_required_types) = stc.prepend_required_types {
-        if prepend_required_types == true {
-            output.push_str("pub trait ToAssign {}");
-            output.push_str("#[derive(Debug,Clone,Copy)]");
-            output.push_str("pub struct Yes {}");
-            output.push_str("#[derive(Debug,Clone,Copy)]");
-            output.push_str("pub struct No {}");
-            output.push_str("impl ToAssign for Yes {}");
-            output.push_str("impl ToAssign for No {}");
-            output.push_str("\n");
-        }
-    }
-
-    // dump derives, if any
-    if let Some(ref derive) = stc.derive {
-        output.push_str(&format!("#[derive({})]\n", derive));
-    }
-    // create the struct
-    {
-        output.push_str(&format!(
-            "pub struct {}{}\n{} {{\n",
-            stc.name,
-            calculate_type_description(&stc, &[], None),
-            calculate_where(&stc, &[])
-        ));
-
-        // constructor types
-        for t in stc.constructor_fields.iter() {
-            output.push_str(&format!("\t{}: {},\n", t.name, t.field_type));
-        }
-
-        // phantom types
-        for f in stc.fields.iter().filter(|f| !f.optional) {
-            output.push_str(&format!(
-                "\tp_{}: PhantomData<{}>,\n",
-                f.name,
-                f.clone().builder_type.unwrap()
-            ));
-        }
-
-        for f in &stc.fields {
-            output.push_str(&format!("\t{}: {},\n", f.name, calculate_type(f)));
-        }
-
-        output.push_str("}\n\n");
-    }
-
-    // create the ctor
-    {
-        output.push_str(&format!(
-            "impl{} {}{} {} {{\n",
-            calculate_type_description(&stc, &all_builder_types, None),
-            stc.name,
-            calculate_type_description_all(&stc, YesNo::No),
-            calculate_where(&stc, &all_builder_types)
-        ));
-
-        if stc.inline() {
-            output.push_str("#[inline]\n");
-        }
-
-        output.push_str(&format!(
-            "\t pub(crate) fn new({}) -> {}{} {{\n\t\t{} {{\n",
-            calculate_constructor_parameters(&stc),
-            stc.name,
-            calculate_type_description_all(&stc, YesNo::No),
-            stc.name
-        ));
-
-        for cp in stc.constructor_fields.iter() {
-            output.push_str(&format!("\t\t\t{},\n", cp.name));
-        }
-
-        for f in stc.fields.iter().filter(|f| !f.optional) {
-            output.push_str(&format!("\t\t\tp_{}: PhantomData {{}},\n", f.name));
-            match f.initializer {
-                Some(ref initializer) => {
-                    output.push_str(&format!("\t\t\t{}: {},\n", f.name, initializer))
+    let mut against = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out-dir" => match args.next() {
+                Some(dir) => options.out_dir = Some(PathBuf::from(dir)),
+                None => return Err("--out-dir requires a directory".to_owned()),
+            },
+            "--catalog" => match args.next() {
+                Some(file) => options.catalog = Some(PathBuf::from(file)),
+                None => return Err("--catalog requires a file".to_owned()),
+            },
+            "--catalog-traits" => match args.next() {
+                Some(file) => options.catalog_traits = Some(PathBuf::from(file)),
+                None => return Err("--catalog-traits requires a file".to_owned()),
+            },
+            "--emit-prelude" => match args.next() {
+                Some(file) => options.prelude = Some(PathBuf::from(file)),
+                None => return Err("--emit-prelude requires a file".to_owned()),
+            },
+            "--typestate" => match args.next() {
+                Some(file) => options.typestate = Some(PathBuf::from(file)),
+                None => return Err("--typestate requires a file".to_owned()),
+            },
+            "--force" => options.force = true,
+            "--lenient" => options.lenient = true,
+            "--check" => options.check = true,
+            // --against is --out, spelled the way it reads when checking
+            "--out" | "--against" => match args.next() {
+                Some(file) => {
+                    options.out = Some(PathBuf::from(file));
+                    against = arg == "--against";
                 }
-                None => output.push_str(&format!("\t\t\t{}: None,\n", f.name)),
-            };
+                None => return Err(format!("{} requires a file", arg)),
+            },
+            "-h" | "--help" => return Err(String::new()),
+            _ if arg.starts_with("--") => return Err(format!("unknown option {}", arg)),
+            _ => options.inputs.push(arg),
         }
-
-        for f in stc.fields.iter().filter(|f| f.optional) {
-            match f.initializer {
-                Some(ref initializer) => {
-                    output.push_str(&format!("\t\t\t{}: {},\n", f.name, initializer))
-                }
-                None => output.push_str(&format!("\t\t\t{}: None,\n", f.name)),
-            };
-        }
-
-        output.push_str("\t\t}\n\t}\n}\n\n");
     }
 
-    // constructor types getter
-    {
-        // first the one with trait
-        for ct in stc
-            .constructor_fields
-            .iter()
-            .filter(|ct| ct.trait_get.is_some())
-        {
-            let t = ct.trait_get.clone().unwrap();
-            output.push_str(&format!(
-                "impl{} {} for {}{}\n",
-                calculate_type_description(&stc, &[], None),
-                t,
-                stc.name,
-                calculate_type_description(&stc, &[], None),
-            ));
-
-            output.push_str(&format!("{}{{\n", &calculate_where(&stc, &[])));
-
-            if stc.inline() {
-                output.push_str("#[inline]\n");
-            }
-
-            // get_via_clone handling
-            let get_via_clone = if let Some(get_via_clone) = ct.get_via_clone {
-                get_via_clone
-            } else {
-                false
-            };
-
-            output.push_str(&format!(
-                "\tfn {}(&self) -> {} {{\n\t\tself.{}{}\n\t}}\n\n",
-                ct.name,
-                ct.field_type,
-                ct.name,
-                if get_via_clone { ".clone()" } else { "" }
-            ));
-
-            output.push_str("}\n\n");
-        }
-
-        // now the ones without trait
-        for ct in stc
-            .constructor_fields
-            .iter()
-            .filter(|ct| ct.trait_get.is_none())
-        {
-            // get_via_clone handling
-            let get_via_clone = if let Some(get_via_clone) = ct.get_via_clone {
-                get_via_clone
-            } else {
-                false
-            };
-
-            //println!("\n\nct ==> {:?}", ct);
-            //println!("regardless  ==> {}", regardless);
-            //regardless.push_str(&format!("{}{{\n", &calculate_where(&stc, &[])));
-            if stc.inline() {
-                output.push_str("#[inline]\n");
-            }
-            regardless.push_str(&format!(
-                "\tfn {}(&self) -> {} {{\n\t\tself.{}{}\n\t}}\n\n",
-                ct.name,
-                ct.field_type,
-                ct.name,
-                if get_via_clone { ".clone()" } else { "" }
-            ));
-            //println!("regardless  ==> {}", regardless);
-        }
+    if options.inputs.is_empty() && options.prelude.is_none() {
+        return Err("no definition file given".to_owned());
     }
-
-    // get mandatory no traits methods
-    {
-        output.push_str("\n//get mandatory no traits methods\n");
-        for tm in stc
-            .fields
-            .iter()
-            .filter(|tm| tm.trait_get.is_none() && tm.optional == false)
-        {
-            let bt = match tm.clone().builder_type {
-                Some(bt) => vec![bt],
-                None => Vec::new(),
-            };
-
-            // get_via_clone handling
-            let get_via_clone = if let Some(get_via_clone) = tm.get_via_clone {
-                get_via_clone
-            } else {
-                false
-            };
-
-            output.push_str(&format!(
-                "impl{} {}{}\n",
-                calculate_type_description(&stc, &bt[..], None),
-                stc.name,
-                calculate_type_description(&stc, &bt[..], Some(YesNo::Yes)),
-            ));
-
-            output.push_str(&format!("{}\n{{\n", calculate_where(&stc, &bt[..])));
-
-            if stc.inline() {
-                output.push_str("#[inline]\n");
-            }
-            output.push_str(&format!("\tpub fn {}(&self) -> ", tm.name));
-
-            if tm.optional && tm.initializer.is_none() {
-                output.push_str(&format!("Option<{}> {{\n", tm.field_type));
-            } else {
-                output.push_str(&format!("{} {{\n", tm.field_type));
-            }
-
-            output.push_str(&format!(
-                "\t\tself.{}{}",
-                tm.name,
-                if get_via_clone { ".clone()" } else { "" }
-            ));
-            if !tm.optional && tm.initializer.is_none() {
-                output.push_str(".unwrap()\n\t}\n}\n\n");
-            } else {
-                output.push_str("\n\t}\n}\n\n");
-            }
-        }
+    if options.out.is_some() && options.out_dir.is_some() {
+        return Err("--out and --out-dir cannot be used together".to_owned());
     }
-
-    // set mandatory no trait methods
-    output.push_str("\n//set mandatory no traits methods\n");
-    {
-        for tm in stc
-            .fields
-            .iter()
-            .filter(|tm| tm.trait_get.is_none() && tm.optional == false)
-        {
-            let bt = match tm.clone().builder_type {
-                Some(bt) => vec![bt],
-                None => Vec::new(),
-            };
-
-            output.push_str(&format!(
-                "impl{} {}{}\n",
-                calculate_type_description(&stc, &bt[..], None),
-                stc.name,
-                calculate_type_description(&stc, &bt[..], Some(YesNo::No)),
-            ));
-
-            output.push_str(&format!("{}\n{{\n", calculate_where(&stc, &bt[..])));
-
-            let return_type = format!(
-                "{}{}",
-                stc.name,
-                calculate_type_description(&stc, &bt[..], Some(YesNo::Yes))
-            );
-
-            if stc.inline() {
-                output.push_str("#[inline]\n");
-            }
-            output.push_str(&format!(
-                "\tpub fn with_{}(self, {}: {}) -> {} {{\n",
-                tm.name, tm.name, tm.field_type, return_type
-            ));
-
-            output.push_str(&format!("\t\t{} {{\n", stc.name));
-
-            // constructor types
-            for t in stc.constructor_fields.iter() {
-                output.push_str(&format!("\t\t\t\t{}: self.{},\n", t.name, t.name));
-            }
-
-            // phantom types
-            for f in stc.fields.iter().filter(|f| !f.optional) {
-                output.push_str(&format!("\t\t\t\tp_{}: PhantomData{{}},\n", f.name,));
-            }
-
-            for f in &stc.fields {
-                if f.name == tm.name {
-                    if tm.initializer.is_some() {
-                        output.push_str(&format!("\t\t\t\t{},\n", f.name));
-                    } else {
-                        output.push_str(&format!("\t\t\t\t{}: Some({}),\n", f.name, f.name));
-                    }
-                } else {
-                    output.push_str(&format!("\t\t\t\t{}: self.{},\n", f.name, f.name));
-                }
-            }
-
-            output.push_str("\t\t}\n\t}\n}\n\n");
-        }
+    if against && !options.check {
+        return Err("--against can only be used with --check".to_owned());
     }
-
-    // get traits methods
-    {
-        for tm in stc.fields.iter().filter(|tm| tm.trait_get.is_some()) {
-            let bt = match tm.clone().builder_type {
-                Some(bt) => vec![bt],
-                None => Vec::new(),
-            };
-            let tg = tm.trait_get.clone().unwrap();
-
-            // get_via_clone handling
-            let get_via_clone = if let Some(get_via_clone) = tm.get_via_clone {
-                get_via_clone
-            } else {
-                false
-            };
-
-            output.push_str(&format!(
-                "impl{} {} for {}{}\n",
-                calculate_type_description(&stc, &bt[..], None),
-                tg,
-                stc.name,
-                calculate_type_description(&stc, &bt[..], Some(YesNo::Yes)),
-            ));
-
-            output.push_str(&format!("{}\n{{\n", calculate_where(&stc, &bt[..])));
-
-            if stc.inline() {
-                output.push_str("#[inline]\n");
-            }
-            output.push_str(&format!("\tfn {}(&self) -> ", tm.name));
-
-            if tm.optional && tm.initializer.is_none() {
-                output.push_str(&format!("Option<{}> {{\n", tm.field_type));
-            } else {
-                output.push_str(&format!("{} {{\n", tm.field_type));
-            }
-
-            output.push_str(&format!(
-                "\t\tself.{}{}",
-                tm.name,
-                if get_via_clone { ".clone()" } else { "" }
-            ));
-            if !tm.optional && tm.initializer.is_none() {
-                output.push_str(".unwrap()\n\t}\n}\n\n");
-            } else {
-                output.push_str("\n\t}\n}\n\n");
-            }
-        }
+    if options.catalog_traits.is_some() && options.catalog.is_none() {
+        return Err("--catalog-traits requires --catalog".to_owned());
     }
-
-    // set trait methods
+    if options.check
+        && options.out.is_none()
+        && options.out_dir.is_none()
+        && (options.prelude.is_none() || !options.inputs.is_empty())
     {
-        for tm in stc.fields.iter().filter(|tm| tm.trait_get.is_some()) {
-            let bt = match tm.clone().builder_type {
-                Some(bt) => vec![bt],
-                None => Vec::new(),
-            };
-            let tg = tm.trait_set.clone().unwrap();
-
-            output.push_str(&format!(
-                "impl{} {} for {}{}\n",
-                calculate_type_description(&stc, &bt[..], None),
-                tg,
-                stc.name,
-                calculate_type_description(&stc, &bt[..], Some(YesNo::No)),
-            ));
-
-            output.push_str(&format!("{}\n{{\n", calculate_where(&stc, &bt[..])));
-
-            output.push_str(&format!(
-                "\ttype O = {}{};\n\n",
-                stc.name,
-                calculate_type_description(&stc, &bt[..], Some(YesNo::Yes))
-            ));
-
-            if stc.inline() {
-                output.push_str("#[inline]\n");
-            }
-            output.push_str(&format!(
-                "\tfn with_{}(self, {}: {}) -> Self::O {{\n",
-                tm.name, tm.name, tm.field_type
-            ));
-
-            output.push_str(&format!("\t\t{} {{\n", stc.name));
-
-            // constructor types
-            for t in stc.constructor_fields.iter() {
-                output.push_str(&format!("\t\t\t\t{}: self.{},\n", t.name, t.name));
-            }
-
-            // phantom types
-            for f in stc.fields.iter().filter(|f| !f.optional) {
-                output.push_str(&format!("\t\t\t\tp_{}: PhantomData{{}},\n", f.name,));
-            }
-
-            for f in &stc.fields {
-                if f.name == tm.name {
-                    if tm.initializer.is_some() {
-                        output.push_str(&format!("\t\t\t\t{},\n", f.name));
-                    } else {
-                        output.push_str(&format!("\t\t\t\t{}: Some({}),\n", f.name, f.name));
-                    }
-                } else {
-                    output.push_str(&format!("\t\t\t\t{}: self.{},\n", f.name, f.name));
-                }
-            }
-
-            output.push_str("\t\t}\n\t}\n}\n\n");
-        }
+        return Err("--check requires --against or --out-dir".to_owned());
     }
+    Ok(options)
+}
 
-    // get optional without traits
-    {
-        for tm in stc
-            .fields
-            .iter()
-            .filter(|tm| tm.optional && tm.trait_get.is_none())
-        {
-            // get_via_clone handling
-            let get_via_clone = if let Some(get_via_clone) = tm.get_via_clone {
-                get_via_clone
+// returns false if --check found differences
+fn run(options: &Options) -> Result<bool, Error> {
+    let catalog = match options.catalog {
+        Some(ref path) => Some(
+            if options.lenient {
+                bpb::load_catalog_lenient(path)
             } else {
-                false
-            };
-
-            if stc.inline() {
-                regardless.push_str("#[inline]\n");
+                bpb::load_catalog(path)
             }
-            regardless.push_str(&format!(
-                "\tpub fn {}(&self) -> Option<{}> {{\n",
-                tm.name, tm.field_type
-            ));
+            .map_err(|e| e.in_file(path.as_path()))?,
+        ),
+        None => None,
+    };
 
-            regardless.push_str(&format!(
-                "\t\tself.{}{}\n",
-                tm.name,
-                if get_via_clone { ".clone()" } else { "" }
-            ));
-            regardless.push_str("\t}\n\n");
+    let typestate = match options.typestate {
+        Some(ref path) => if options.lenient {
+            bpb::load_typestate_lenient(path)
+        } else {
+            bpb::load_typestate(path)
         }
-    }
-
-    // set optional without traits
-    {
-        for tm in stc
-            .fields
-            .iter()
-            .filter(|tm| tm.optional && tm.trait_get.is_none())
-        {
-            if stc.inline() {
-                regardless.push_str("#[inline]\n");
-            }
-            regardless.push_str(&format!(
-                "\tfn with_{}(self, {}: {}) -> Self {{\n",
-                tm.name, tm.name, tm.field_type
-            ));
-
-            regardless.push_str(&format!("\t\t{} {{\n", stc.name));
-
-            // constructor types
-            for t in stc.constructor_fields.iter() {
-                regardless.push_str(&format!("\t\t\t\t{}: self.{},\n", t.name, t.name));
-            }
-
-            // phantom types
-            for f in stc.fields.iter().filter(|f| !f.optional) {
-                regardless.push_str(&format!("\t\t\t\tp_{}: self.p_{},\n", f.name, f.name));
-            }
-
-            for f in &stc.fields {
-                if f.name == tm.name {
-                    regardless.push_str(&format!("\t\t\t\t{}: Some({}),\n", f.name, f.name));
-                } else {
-                    regardless.push_str(&format!("\t\t\t\t{}: self.{},\n", f.name, f.name));
-                }
-            }
+        .map_err(|e| e.in_file(path.as_path()))?,
+        None => bpb::Typestate::default(),
+    };
 
-            regardless.push_str("\t\t}\n\t}\n\n");
+    let mut outputs = Vec::new();
+    for input in options.inputs.iter() {
+        let structs = match catalog {
+            _ if options.lenient => bpb::load_all_lenient(input, catalog.as_ref()),
+            Some(ref catalog) => bpb::load_all_with_catalog(input, catalog),
+            None => bpb::load_all(input),
+        };
+        for mut stc in structs.map_err(|e| e.in_file(input.as_str()))? {
+            stc.typestate = stc.typestate.or(&typestate);
+            let output = bpb::generate(&stc).map_err(|e| e.in_file(input.as_str()))?;
+            let source = format!("`{}` from {}", stc.name, input);
+            outputs.push((stc.name, source, output));
         }
     }
 
-    // print regardless
-    {
-        output.push_str("// methods callable regardless\n");
-        output.push_str(&format!(
-            "impl{} {}{}\n",
-            calculate_type_description(&stc, &[], None),
-            stc.name,
-            calculate_type_description(&stc, &[], None)
+    // the path, what is written to it and its contents
+    let mut files: Vec<(PathBuf, String, String)> = Vec::new();
+    if let (Some(catalog), Some(path)) = (catalog, options.catalog_traits.as_ref()) {
+        let output = bpb::generate_catalog(&catalog)?;
+        files.push((
+            path.clone(),
+            "the catalog traits".to_owned(),
+            bpb::render(&[output]),
         ));
-
-        output.push_str(&format!("{}\n", calculate_where(&stc, &[])));
-
-        output.push_str(&format!("{{\n{}\n", &regardless));
-        output.push_str("}\n");
     }
-
-    // print final
-    {
-        output.push_str("\n// methods callable only when every mandatory field has been filled\n");
-        output.push_str(&format!(
-            "impl{} {}{}\n",
-            calculate_type_description(&stc, &all_builder_types, None),
-            stc.name,
-            calculate_type_description_all(&stc, YesNo::Yes),
+    if let Some(ref path) = options.prelude {
+        files.push((
+            path.clone(),
+            "the typestate prelude".to_owned(),
+            bpb::render(&[bpb::generate_prelude(&typestate)?]),
         ));
-
-        output.push_str(&format!("{}\n", calculate_where(&stc, &all_builder_types)));
-
-        //output.push_str(&format!("{{\n{}\n", &regardless));
-        output.push_str("{\n");
-        output.push_str("}\n");
-    }
-
-    println!("\n{}", output);
-}
-
-fn calculate_type(f: &Field) -> String {
-    match f.initializer {
-        Some(_) => f.field_type.to_owned(),
-        None => format!("Option<{}>", f.field_type),
     }
-}
-
-fn calculate_constructor_parameters(stc: &Struct) -> String {
-    let mut s = String::new();
-    let mut f_first = true;
 
-    for cp in stc.constructor_fields.iter() {
-        if !f_first {
-            s.push_str(", ");
+    if let Some(ref out_dir) = options.out_dir {
+        for (name, source, output) in outputs {
+            let out_file = out_dir.join(format!("{}.rs", bpb::snake_case(&name)));
+            files.push((out_file, source, bpb::render(&[output])));
+        }
+    } else {
+        let outputs = outputs.into_iter().map(|(_, _, o)| o).collect::<Vec<_>>();
+        match options.out {
+            Some(ref out) => files.push((
+                out.clone(),
+                "the builders".to_owned(),
+                bpb::render(&outputs),
+            )),
+            None if !outputs.is_empty() => print!("{}", bpb::render(&outputs)),
+            None => {}
         }
-        s.push_str(&format!("{}: {}", cp.name, cp.field_type));
-        f_first = false;
     }
-    s
-}
-
-fn calculate_type_description_all(stc: &Struct, yes_no: YesNo) -> String {
-    let mut s = String::new();
 
-    let mut f_first = true;
-    for f in &stc.extra_types {
-        if !f_first {
-            s.push_str(", ");
+    // nothing is written, or checked, if two outputs go to the same file
+    for (i, (path, source, _)) in files.iter().enumerate() {
+        if let Some((_, first, _)) = files[..i].iter().find(|f| f.0 == *path) {
+            return Err(Error::Clash(path.clone(), first.clone(), source.clone()));
         }
-
-        s.push_str(&f);
-        f_first = false;
     }
 
-    for _ in stc.fields.iter().filter(|f| f.optional == false) {
-        if !f_first {
-            match yes_no {
-                YesNo::Yes => s.push_str(", Yes"),
-                YesNo::No => s.push_str(", No"),
+    if options.check {
+        let mut up_to_date = true;
+        for (path, _, contents) in files {
+            if let Some(diff) = bpb::check_file(&path, &contents)? {
+                eprintln!("{}: out of date", path.display());
+                print!("{}", diff);
+                up_to_date = false;
             }
         }
-
-        f_first = false;
-    }
-
-    if s.is_empty() {
-        "".to_owned()
-    } else {
-        format!("<{}>", s)
+        return Ok(up_to_date);
     }
-}
 
-fn calculate_type_description(
-    stc: &Struct,
-    builders_type_to_skip: &[String],
-    replace_with: Option<YesNo>,
-) -> String {
-    let mut s = String::new();
-
-    let mut f_first = true;
-    for f in &stc.extra_types {
-        if !f_first {
-            s.push_str(", ");
+    // nor if one of the files is hand written
+    if !options.force {
+        for (path, _, _) in files.iter() {
+            bpb::check_overwritable(path)?;
         }
-
-        s.push_str(&f);
-        f_first = false;
     }
 
-    for f in stc.fields.iter().filter(|f| f.optional == false) {
-        let bt = f.builder_type.clone().unwrap();
-        if builders_type_to_skip.contains(&bt) {
-            if let Some(yes_no) = replace_with {
-                if !f_first {
-                    s.push_str(", ");
-                }
-
-                match yes_no {
-                    YesNo::Yes => s.push_str("Yes"),
-                    YesNo::No => s.push_str("No"),
-                }
-                f_first = false;
-            }
-        } else {
-            if !f_first {
-                s.push_str(", ");
-            }
-
-            let bt = match f.builder_type {
-                Some(ref a) => a,
-                None => panic!(),
-            };
-
-            s.push_str(&bt);
-            f_first = false;
-        }
+    if let Some(ref out_dir) = options.out_dir {
+        fs::create_dir_all(out_dir).map_err(|e| Error::from(e).in_file(out_dir.as_path()))?;
     }
-
-    if s.is_empty() {
-        "".to_owned()
-    } else {
-        format!("<{}>", s)
+    for (path, _, contents) in files {
+        bpb::write_file(&path, &contents, options.force)?;
     }
+    Ok(true)
 }
 
-fn calculate_where(stc: &Struct, builders_type_to_skip: &[String]) -> String {
-    let mut s = String::new();
-
-    for f in stc.fields.iter().filter(|f| !f.optional).filter(|f| {
-        let bt = f.builder_type.clone().unwrap();
-        !builders_type_to_skip.contains(&bt)
-    }) {
-        s.push_str(&format!(
-            "\t{} : ToAssign,\n",
-            f.clone().builder_type.unwrap()
-        ));
+fn main() {
+    if std::env::args().nth(1).as_deref() == Some("schema") {
+        if std::env::args().len() > 2 {
+            eprintln!("bpb: schema takes no arguments\n\n{}", USAGE);
+            std::process::exit(2);
+        }
+        print!("{}", bpb::schema());
+        return;
     }
 
-    for ew in stc.extra_wheres.iter() {
-        s.push_str(&format!("\t{},\n", ew));
-    }
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(ref message) if message.is_empty() => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(message) => {
+            eprintln!("bpb: {}\n\n{}", message, USAGE);
+            std::process::exit(2);
+        }
+    };
 
-    if s.is_empty() {
-        "".to_owned()
-    } else {
-        format!("where\n{}", s)
+    match run(&options) {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(e @ Error::NotGenerated(_)) => {
+            eprintln!("{} (use --force to overwrite it anyway)", e);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
     }
 }
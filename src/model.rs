@@ -0,0 +1,629 @@
+use schemars::JsonSchema;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+/// A field that can be set through the builder. Required fields get a
+/// typestate parameter named after `builder_type`.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct Field {
+    /// The name of the field, its getter and its setter `with_<name>`.
+    #[serde(default)]
+    pub name: String,
+    /// The Rust type of the field.
+    #[serde(default)]
+    pub field_type: String,
+    /// The typestate parameter of a required field.
+    pub builder_type: Option<String>,
+    /// The field may be left unset; its getter returns an `Option`.
+    pub optional: Option<bool>,
+    /// The default value of the field, also accepted as `default`. A field
+    /// with a default is stored as `field_type` rather than as an `Option`,
+    /// and its getter returns `field_type` even if the field is optional.
+    #[serde(alias = "default")]
+    pub initializer: Option<String>,
+    /// Default the field to `Default::default()`.
+    pub default_trait: Option<bool>,
+    /// The trait the getter is implemented for, e.g. `TimeoutOption`.
+    pub trait_get: Option<String>,
+    /// The trait the setter is implemented for, e.g. `TimeoutSupport`.
+    pub trait_set: Option<String>,
+    /// Let the getter return a clone of the field instead of a copy.
+    pub get_via_clone: Option<bool>,
+    /// Let the setter take any `V: Into<field_type>`.
+    pub setter_into: Option<bool>,
+    /// Let the setter of a `&'a U` field take any `&'a V` where
+    /// `V: AsRef<U>`.
+    pub setter_as_ref: Option<bool>,
+    /// Let the setter take an `impl Into<field_type>`.
+    pub setter_impl_trait: Option<bool>,
+    /// The field is a `Vec`, `HashSet`, `HashMap` or `BTreeMap`, given by
+    /// `field_type`, that can be filled an item at a time.
+    pub collection: Option<bool>,
+    /// The id of the catalog entry this field is taken from. Whatever the
+    /// field leaves out is filled in from the entry.
+    #[serde(rename = "use")]
+    pub catalog_entry: Option<String>,
+    /// The visibility of the getter and setter, if they are not trait
+    /// methods. Defaults to the visibility of the struct.
+    pub visibility: Option<String>,
+    /// Let the naming convention of the struct name the `trait_get` and
+    /// `trait_set` the field leaves out, `true` by default. With `false`
+    /// the getter and setter stay methods of the builder.
+    pub traits: Option<bool>,
+}
+
+/// A field passed to `new` and never changed afterwards.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ConstructorField {
+    /// The name of the field, its getter and its parameter of `new`.
+    pub name: String,
+    /// The Rust type of the field.
+    pub field_type: String,
+    /// The trait the getter is implemented for.
+    pub trait_get: Option<String>,
+    /// Let the getter return a clone of the field instead of a copy.
+    pub get_via_clone: Option<bool>,
+    /// The visibility of the getter, if it is not a trait method. Defaults
+    /// to the visibility of the struct.
+    pub visibility: Option<String>,
+    /// Let the naming convention of the struct name the `trait_get` the
+    /// field leaves out, `true` by default. With `false` the getter stays a
+    /// method of the builder.
+    pub traits: Option<bool>,
+}
+
+/// A method emitted in the impl block that is only available once every
+/// required field has been set.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct Finalizer {
+    /// The name of the method.
+    pub name: String,
+    /// The return type of the method, `()` if left out.
+    pub return_type: Option<String>,
+    /// Take `&self` instead of consuming the builder.
+    pub by_ref: Option<bool>,
+    /// Make the method `async`.
+    #[serde(rename = "async")]
+    pub is_async: Option<bool>,
+    /// The body of the method, `todo!()` if left out. It is emitted as
+    /// written, only indented, so it should be formatted as rustfmt would.
+    pub body: Option<String>,
+    /// The visibility of the method. Defaults to the visibility of the
+    /// struct.
+    pub visibility: Option<String>,
+}
+
+/// The definition of a builder, as read from a definition file.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct Struct {
+    /// The name of the builder struct.
+    pub name: String,
+    /// The traits derived by the builder, e.g. `Debug, Clone`.
+    pub derive: Option<String>,
+    /// The paths imported with `use` at the top of the output.
+    #[serde(default)]
+    pub uses: Vec<String>,
+    /// Mark the methods of the builder `#[inline]`.
+    pub inline: Option<bool>,
+    /// The lifetimes, type parameters and const parameters of the builder.
+    #[serde(default)]
+    pub extra_types: Vec<ExtraType>,
+    /// The predicates added to the `where` clauses, e.g.
+    /// `P: IntoAzurePath`.
+    #[serde(default)]
+    pub extra_wheres: Vec<String>,
+    /// Put the bounds of `extra_types` in the `where` clauses instead of
+    /// the generic parameter lists.
+    pub bounds_in_where: Option<bool>,
+    /// Emit `ToAssign` and the typestate markers before the builder,
+    /// instead of expecting them to be in scope.
+    pub prepend_required_types: Option<bool>,
+    /// The module holding the typestate prelude, e.g. `crate::typestate`.
+    /// When set, the builder imports `ToAssign` and the markers from it.
+    pub prelude_path: Option<String>,
+    /// How the typestate markers of this builder are named.
+    #[serde(default)]
+    pub typestate: Typestate,
+    /// The fields passed to `new`.
+    #[serde(default)]
+    pub constructor_fields: Vec<ConstructorField>,
+    /// The fields set through the builder.
+    pub fields: Vec<Field>,
+    /// The methods available once every required field is set.
+    #[serde(default)]
+    pub finalizers: Vec<Finalizer>,
+    /// Emit the definitions of the getter and setter traits referenced by
+    /// the fields, instead of expecting them to exist already.
+    pub emit_traits: Option<bool>,
+    /// The visibility of the struct, of `new` and of the getters and
+    /// setters: `pub`, the default, `pub(crate)`, `pub(super)` or
+    /// `private`. If it is not set, `new` stays `pub(crate)`.
+    pub visibility: Option<String>,
+    /// Derive the `builder_type`, `trait_get` and `trait_set` the fields
+    /// leave out from their names.
+    pub naming: Option<Naming>,
+}
+
+/// The `visibility` meaning no visibility qualifier at all.
+pub const PRIVATE: &str = "private";
+
+/// A reusable field definition, referenced by id from the `use` key of a
+/// builder field.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CatalogEntry {
+    pub name: String,
+    pub field_type: String,
+    pub builder_type: Option<String>,
+    pub optional: Option<bool>,
+    #[serde(alias = "default")]
+    pub initializer: Option<String>,
+    pub default_trait: Option<bool>,
+    pub trait_get: Option<String>,
+    pub trait_set: Option<String>,
+    pub get_via_clone: Option<bool>,
+    pub setter_into: Option<bool>,
+    pub setter_as_ref: Option<bool>,
+    pub setter_impl_trait: Option<bool>,
+    pub collection: Option<bool>,
+    /// The lifetimes and type parameters used by the traits.
+    #[serde(default)]
+    pub extra_types: Vec<ExtraType>,
+    #[serde(default)]
+    pub extra_wheres: Vec<String>,
+}
+
+/// Field definitions shared by several builders, keyed by id.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Catalog {
+    pub fields: BTreeMap<String, CatalogEntry>,
+    /// The module the catalog traits are emitted into. When set, builders
+    /// import the catalog traits they use from it instead of emitting them.
+    pub module: Option<String>,
+}
+
+/// A generic parameter of the builder other than the typestates.
+///
+/// Written either as a string, such as `"'a"`, `"T: Clone"` or
+/// `"const N: usize"`, or as an object: `{"lifetime": "'a", "bounds":
+/// ["'b"]}`, `{"type": "T", "bounds": ["Clone"]}` or `{"const": "N",
+/// "type": "usize"}`.
+#[derive(Debug, Clone, PartialEq, Deserialize, JsonSchema)]
+#[serde(try_from = "ExtraTypeDef")]
+pub enum ExtraType {
+    Lifetime { name: String, bounds: Vec<String> },
+    Type { name: String, bounds: Vec<String> },
+    Const { name: String, const_type: String },
+}
+
+// the accepted spellings of an `ExtraType`
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+#[schemars(deny_unknown_fields)]
+enum ExtraTypeDef {
+    Plain(String),
+    Object {
+        lifetime: Option<String>,
+        #[serde(rename = "type")]
+        type_: Option<String>,
+        #[serde(rename = "const")]
+        const_: Option<String>,
+        #[serde(default)]
+        bounds: Vec<String>,
+    },
+}
+
+/// How the typestate markers are named and generated.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct Typestate {
+    /// The marker of a required field that has been set, `Yes` by default.
+    pub set: Option<String>,
+    /// The marker of a required field that has not been set yet, `No` by
+    /// default.
+    pub unset: Option<String>,
+    /// Seal `ToAssign` so that no other crate can implement it. On by
+    /// default.
+    pub sealed: Option<bool>,
+    /// Generate the markers as enums without variants, which cannot be
+    /// instantiated, instead of empty structs.
+    pub enums: Option<bool>,
+}
+
+/// The naming convention of a builder. In each template, `{}` stands for
+/// the name of the field in upper camel case; the traits also take the
+/// lifetimes and type parameters of the builder found in `field_type`.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct Naming {
+    /// The typestate parameter of a required field, `{}Set` by default.
+    pub builder_type: Option<String>,
+    /// The getter trait of a required or constructor field, `{}Required`
+    /// by default.
+    pub trait_get_required: Option<String>,
+    /// The getter trait of an optional field, `{}Option` by default.
+    pub trait_get_optional: Option<String>,
+    /// The setter trait, `{}Support` by default.
+    pub trait_set: Option<String>,
+    /// Name the getter and setter traits, not only the typestate
+    /// parameters. On by default.
+    pub traits: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum YesNo {
+    Yes,
+    No,
+}
+
+/// The kinds of `collection` fields, named after their type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Collection {
+    Vec,
+    HashSet,
+    HashMap,
+    BTreeMap,
+}
+
+impl Collection {
+    /// The collection kind of `field_type` and its type arguments, e.g.
+    /// `(HashMap, ["String", "String"])` for
+    /// `std::collections::HashMap<String, String>`.
+    pub fn of(field_type: &str) -> Option<(Collection, Vec<String>)> {
+        let field_type = field_type.trim();
+        let start = field_type.find('<')?;
+        if !field_type.ends_with('>') {
+            return None;
+        }
+
+        let path = field_type[..start].trim();
+        let kind = match path.rsplit("::").next().unwrap_or(path).trim() {
+            "Vec" => Collection::Vec,
+            "HashSet" => Collection::HashSet,
+            "HashMap" => Collection::HashMap,
+            "BTreeMap" => Collection::BTreeMap,
+            _ => return None,
+        };
+
+        let args = split_top_level(&field_type[start + 1..field_type.len() - 1], ',');
+        if args.len() != kind.arity() {
+            return None;
+        }
+        Some((kind, args))
+    }
+
+    /// The number of type arguments.
+    pub fn arity(self) -> usize {
+        match self {
+            Collection::Vec | Collection::HashSet => 1,
+            Collection::HashMap | Collection::BTreeMap => 2,
+        }
+    }
+}
+
+/// What the setter of a field accepts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Setter {
+    /// The field type itself.
+    Exact,
+    /// A generic `V: Into<field_type>`.
+    Into,
+    /// A `&'a V` where `V: AsRef<U>`, for a field of type `&'a U`.
+    AsRef,
+    /// An `impl Into<field_type>`.
+    ImplTrait,
+}
+
+impl Field {
+    pub fn optional(&self) -> bool {
+        self.optional.unwrap_or_default()
+    }
+
+    pub fn collection(&self) -> bool {
+        self.collection.unwrap_or_default()
+    }
+
+    pub fn default_trait(&self) -> bool {
+        self.default_trait.unwrap_or_default()
+    }
+
+    /// The default value the field starts out with, if it has one and is
+    /// therefore not stored as an `Option`. Collections start out empty
+    /// unless told otherwise.
+    pub fn initializer(&self) -> Option<&str> {
+        match self.initializer {
+            Some(ref initializer) => Some(initializer),
+            None if self.default_trait() || self.collection() => Some("Default::default()"),
+            None => None,
+        }
+    }
+
+    /// The kind of setter, the first of `setter_into`, `setter_as_ref` and
+    /// `setter_impl_trait` that is set.
+    pub fn setter(&self) -> Setter {
+        if self.setter_into.unwrap_or_default() {
+            Setter::Into
+        } else if self.setter_as_ref.unwrap_or_default() {
+            Setter::AsRef
+        } else if self.setter_impl_trait.unwrap_or_default() {
+            Setter::ImplTrait
+        } else {
+            Setter::Exact
+        }
+    }
+}
+
+impl Struct {
+    pub fn inline(&self) -> bool {
+        self.inline.unwrap_or_default()
+    }
+
+    pub fn emit_traits(&self) -> bool {
+        self.emit_traits.unwrap_or_default()
+    }
+
+    pub fn bounds_in_where(&self) -> bool {
+        self.bounds_in_where.unwrap_or_default()
+    }
+}
+
+impl ExtraType {
+    /// The name of the parameter, e.g. `'a`, `T` or `N`.
+    pub fn name(&self) -> &str {
+        match *self {
+            ExtraType::Lifetime { ref name, .. }
+            | ExtraType::Type { ref name, .. }
+            | ExtraType::Const { ref name, .. } => name,
+        }
+    }
+
+    pub fn is_lifetime(&self) -> bool {
+        matches!(*self, ExtraType::Lifetime { .. })
+    }
+
+    /// The parameter as declared in a generic parameter list, with its
+    /// bounds if `with_bounds` is set.
+    pub fn declaration(&self, with_bounds: bool) -> String {
+        match *self {
+            ExtraType::Lifetime {
+                ref name,
+                ref bounds,
+            }
+            | ExtraType::Type {
+                ref name,
+                ref bounds,
+            } => {
+                if with_bounds && !bounds.is_empty() {
+                    format!("{}: {}", name, bounds.join(" + "))
+                } else {
+                    name.clone()
+                }
+            }
+            ExtraType::Const {
+                ref name,
+                ref const_type,
+            } => format!("const {}: {}", name, const_type),
+        }
+    }
+
+    /// The `where` predicate holding the bounds of the parameter, if it has
+    /// any.
+    pub fn predicate(&self) -> Option<String> {
+        match *self {
+            ExtraType::Lifetime {
+                ref name,
+                ref bounds,
+            }
+            | ExtraType::Type {
+                ref name,
+                ref bounds,
+            } if !bounds.is_empty() => Some(format!("{}: {}", name, bounds.join(" + "))),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for ExtraType {
+    /// Reads the string spelling of an extra type. Anything that is not a
+    /// lifetime or a const parameter is taken as a type parameter.
+    fn from(s: &'a str) -> ExtraType {
+        let s = s.trim();
+        if let Some(rest) = s.strip_prefix("const ") {
+            let mut parts = rest.splitn(2, ':');
+            return ExtraType::Const {
+                name: parts.next().unwrap_or_default().trim().to_owned(),
+                const_type: parts.next().unwrap_or_default().trim().to_owned(),
+            };
+        }
+
+        let (name, bounds) = match s.find(':') {
+            Some(colon) => (s[..colon].trim(), split_bounds(&s[colon + 1..])),
+            None => (s, Vec::new()),
+        };
+        if name.starts_with('\'') {
+            ExtraType::Lifetime {
+                name: name.to_owned(),
+                bounds,
+            }
+        } else {
+            ExtraType::Type {
+                name: name.to_owned(),
+                bounds,
+            }
+        }
+    }
+}
+
+impl TryFrom<ExtraTypeDef> for ExtraType {
+    type Error = String;
+
+    fn try_from(def: ExtraTypeDef) -> Result<ExtraType, String> {
+        let extra_type = match def {
+            ExtraTypeDef::Plain(s) => ExtraType::from(s.as_str()),
+            ExtraTypeDef::Object {
+                lifetime: Some(name),
+                type_: None,
+                const_: None,
+                bounds,
+            } => ExtraType::Lifetime { name, bounds },
+            ExtraTypeDef::Object {
+                lifetime: None,
+                type_: Some(name),
+                const_: None,
+                bounds,
+            } => ExtraType::Type { name, bounds },
+            ExtraTypeDef::Object {
+                lifetime: None,
+                type_: Some(const_type),
+                const_: Some(name),
+                ref bounds,
+            } if bounds.is_empty() => ExtraType::Const { name, const_type },
+            ExtraTypeDef::Object {
+                lifetime: None,
+                type_: None,
+                const_: Some(name),
+                ..
+            } => {
+                return Err(format!(
+                    "const parameter `{}` needs a type, as in `{{\"const\": \"{}\", \"type\": \"usize\"}}`",
+                    name, name
+                ))
+            }
+            ExtraTypeDef::Object { .. } => {
+                return Err(
+                    "an extra type is either `{\"lifetime\": ..}`, `{\"type\": ..}` or `{\"const\": .., \"type\": ..}`, optionally with `bounds` for the first two"
+                        .to_owned(),
+                )
+            }
+        };
+
+        match extra_type {
+            ExtraType::Lifetime { ref name, .. } if !name.starts_with('\'') => {
+                Err(format!("lifetime `{}` must start with `'`", name))
+            }
+            ExtraType::Type { ref name, .. } if name.is_empty() || name.starts_with('\'') => {
+                Err(format!("`{}` is not a type parameter", name))
+            }
+            ExtraType::Const {
+                ref name,
+                ref const_type,
+            } if const_type.is_empty() => Err(format!(
+                "const parameter `{}` needs a type, as in `const {}: usize`",
+                name, name
+            )),
+            extra_type => Ok(extra_type),
+        }
+    }
+}
+
+// splits `Clone + Borrow<[u8]> + 'a` at the `+` outside of brackets
+fn split_bounds(bounds: &str) -> Vec<String> {
+    split_top_level(bounds, '+')
+}
+
+// splits at the `separator`s outside of brackets, dropping empty parts
+fn split_top_level(s: &str, separator: char) -> Vec<String> {
+    let mut split = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    let mut previous = ' ';
+    for (i, c) in s.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            // the `>` of `->` closes nothing
+            '>' if previous == '-' => {}
+            '>' | ')' | ']' => depth -= 1,
+            c if c == separator && depth == 0 => {
+                split.push(s[start..i].trim().to_owned());
+                start = i + 1;
+            }
+            _ => {}
+        }
+        previous = c;
+    }
+    split.push(s[start..].trim().to_owned());
+    split.retain(|b| !b.is_empty());
+    split
+}
+
+impl Typestate {
+    /// The name of the marker for `yes_no`.
+    pub fn marker(&self, yes_no: YesNo) -> &str {
+        match yes_no {
+            YesNo::Yes => self.set.as_ref().map_or("Yes", String::as_str),
+            YesNo::No => self.unset.as_ref().map_or("No", String::as_str),
+        }
+    }
+
+    pub fn sealed(&self) -> bool {
+        self.sealed.unwrap_or(true)
+    }
+
+    pub fn enums(&self) -> bool {
+        self.enums.unwrap_or_default()
+    }
+
+    /// This configuration, with the settings it leaves out taken from
+    /// `fallback`.
+    pub fn or(&self, fallback: &Typestate) -> Typestate {
+        Typestate {
+            set: self.set.clone().or_else(|| fallback.set.clone()),
+            unset: self.unset.clone().or_else(|| fallback.unset.clone()),
+            sealed: self.sealed.or(fallback.sealed),
+            enums: self.enums.or(fallback.enums),
+        }
+    }
+}
+
+impl Naming {
+    pub fn builder_type(&self) -> &str {
+        self.builder_type.as_ref().map_or("{}Set", String::as_str)
+    }
+
+    pub fn trait_get_required(&self) -> &str {
+        self.trait_get_required
+            .as_ref()
+            .map_or("{}Required", String::as_str)
+    }
+
+    pub fn trait_get_optional(&self) -> &str {
+        self.trait_get_optional
+            .as_ref()
+            .map_or("{}Option", String::as_str)
+    }
+
+    pub fn trait_set(&self) -> &str {
+        self.trait_set.as_ref().map_or("{}Support", String::as_str)
+    }
+
+    pub fn traits(&self) -> bool {
+        self.traits.unwrap_or(true)
+    }
+}
+
+impl CatalogEntry {
+    /// The builder field described by this entry.
+    pub fn to_field(&self) -> Field {
+        Field {
+            name: self.name.clone(),
+            field_type: self.field_type.clone(),
+            builder_type: self.builder_type.clone(),
+            optional: self.optional,
+            initializer: self.initializer.clone(),
+            default_trait: self.default_trait,
+            trait_get: self.trait_get.clone(),
+            trait_set: self.trait_set.clone(),
+            get_via_clone: self.get_via_clone,
+            setter_into: self.setter_into,
+            setter_as_ref: self.setter_as_ref,
+            setter_impl_trait: self.setter_impl_trait,
+            collection: self.collection,
+            catalog_entry: None,
+            visibility: None,
+            traits: None,
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use model::{ExtraType, Struct};
+
+/// Converts a type name such as `PutBlockListBuilder` to snake case
+/// (`put_block_list_builder`).
+pub fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut s = String::new();
+
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let prev_lower = i > 0 && (chars[i - 1].is_lowercase() || chars[i - 1].is_numeric());
+            let next_lower = i + 1 < chars.len() && chars[i + 1].is_lowercase();
+            if i > 0 && (prev_lower || (next_lower && chars[i - 1].is_uppercase())) {
+                s.push('_');
+            }
+            s.extend(c.to_lowercase());
+        } else {
+            s.push(*c);
+        }
+    }
+    s
+}
+
+/// Converts a field name such as `container_name` to upper camel case
+/// (`ContainerName`).
+pub fn camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Fills in the `builder_type`, `trait_get` and `trait_set` the fields of
+/// `stc` leave out, following its `naming` convention if it has one. The
+/// getter of a constructor field is named like the one of a required
+/// field. Fields with `traits` set to `false` are not given traits.
+pub fn apply_naming(stc: &mut Struct) {
+    let naming = match stc.naming {
+        Some(ref naming) => naming.clone(),
+        None => return,
+    };
+
+    for f in stc.fields.iter_mut() {
+        let name = camel_case(&f.name);
+        if !f.optional() && f.builder_type.is_none() {
+            f.builder_type = Some(naming.builder_type().replace("{}", &name));
+        }
+        if !naming.traits() || !f.traits.unwrap_or(true) {
+            continue;
+        }
+
+        let arguments = trait_arguments(&f.field_type, &stc.extra_types);
+        if f.trait_get.is_none() {
+            let template = if f.optional() {
+                naming.trait_get_optional()
+            } else {
+                naming.trait_get_required()
+            };
+            f.trait_get = Some(template.replace("{}", &name) + &arguments);
+        }
+        if f.trait_set.is_none() {
+            f.trait_set = Some(naming.trait_set().replace("{}", &name) + &arguments);
+        }
+    }
+
+    if naming.traits() {
+        for cf in stc.constructor_fields.iter_mut() {
+            if cf.trait_get.is_none() && cf.traits.unwrap_or(true) {
+                let name = camel_case(&cf.name);
+                cf.trait_get = Some(
+                    naming.trait_get_required().replace("{}", &name)
+                        + &trait_arguments(&cf.field_type, &stc.extra_types),
+                );
+            }
+        }
+    }
+}
+
+// the generic arguments of the traits of a field, the lifetimes and type
+// parameters of `extra_types` appearing in `field_type`, e.g. `<'a, P>`
+fn trait_arguments(field_type: &str, extra_types: &[ExtraType]) -> String {
+    let tokens = tokens(field_type);
+    let arguments = extra_types
+        .iter()
+        .filter(|t| !matches!(**t, ExtraType::Const { .. }))
+        .map(ExtraType::name)
+        .filter(|name| tokens.iter().any(|t| t == name))
+        .collect::<Vec<_>>();
+
+    if arguments.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", arguments.join(", "))
+    }
+}
+
+// the identifiers and lifetimes of a type
+fn tokens(field_type: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (i, c) in field_type.char_indices() {
+        let in_token = c.is_alphanumeric() || c == '_' || (c == '\'' && start.is_none());
+        match (start, in_token) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                tokens.push(&field_type[s..i]);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        tokens.push(&field_type[s..]);
+    }
+    tokens
+}
@@ -0,0 +1,161 @@
+use error::Error;
+use format::compare_uses;
+use similar::TextDiff;
+use std::fs;
+use std::io::prelude::*;
+use std::path::Path;
+
+/// The marker identifying files written by bpb. Only files containing it
+/// in their first lines can be overwritten without forcing.
+pub const MARKER: &str = "@generated by bpb";
+
+/// Builds the contents of a generated file: a header carrying the
+/// [`MARKER`] followed by every generated builder.
+///
+/// The `use` items the builders start with are hoisted above them, sorted
+/// and deduplicated, so that builders importing the same paths can share a
+/// file.
+pub fn render<S: AsRef<str>>(outputs: &[S]) -> String {
+    let mut uses = Vec::new();
+    let mut bodies = Vec::new();
+    for output in outputs {
+        let (output_uses, body) = split_uses(output.as_ref());
+        for u in output_uses {
+            if !uses.contains(&u) {
+                uses.push(u);
+            }
+        }
+        bodies.push(body);
+    }
+    uses.sort_by(|a, b| compare_uses(a, b));
+
+    let mut s = format!("// This file is {}, do not edit it by hand.\n", MARKER);
+    if !uses.is_empty() {
+        s.push('\n');
+        for u in uses {
+            s.push_str(u);
+        }
+    }
+    for body in bodies.into_iter().filter(|b| !b.is_empty()) {
+        s.push('\n');
+        s.push_str(body);
+    }
+    s
+}
+
+// the `use` items at the start of `output`, each with its line break, and
+// what follows them
+fn split_uses(output: &str) -> (Vec<&str>, &str) {
+    let mut uses = Vec::new();
+    let mut rest = output;
+    while starts_with_use(rest) {
+        // use trees hold no `;`, the first one ends the item
+        let end = match rest.find(";\n") {
+            Some(end) => end + ";\n".len(),
+            None => break,
+        };
+        uses.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    if !uses.is_empty() {
+        rest = rest.strip_prefix('\n').unwrap_or(rest);
+    }
+    (uses, rest)
+}
+
+// whether the first line of `s` starts a `use` item, with or without a
+// visibility
+fn starts_with_use(s: &str) -> bool {
+    let line = s.lines().next().unwrap_or_default();
+    let line = if line.starts_with("pub(") {
+        match line.find(") ") {
+            Some(end) => &line[end + ") ".len()..],
+            None => return false,
+        }
+    } else {
+        line.strip_prefix("pub ").unwrap_or(line)
+    };
+    line.starts_with("use ")
+}
+
+/// Returns true if `contents` starts with the header written by [`render`].
+pub fn is_generated(contents: &str) -> bool {
+    contents.lines().take(5).any(|l| l.contains(MARKER))
+}
+
+/// Writes `contents` to `path` by writing a temporary file in the same
+/// directory and renaming it over `path`, so readers never see a partially
+/// written file.
+///
+/// An existing file is only replaced if it was generated by bpb, unless
+/// `force` is set; otherwise `Error::NotGenerated` is returned.
+pub fn write_file<P: AsRef<Path>>(path: P, contents: &str, force: bool) -> Result<(), Error> {
+    let path = path.as_ref();
+
+    if !force {
+        check_overwritable(path)?;
+    }
+
+    let file_name = match path.file_name() {
+        Some(file_name) => file_name.to_string_lossy(),
+        None => {
+            return Err(
+                Error::from(std::io::Error::from(std::io::ErrorKind::InvalidInput)).in_file(path),
+            )
+        }
+    };
+    let tmp = path.with_file_name(format!(".{}.bpb-tmp", file_name));
+
+    let result = fs::File::create(&tmp)
+        .and_then(|mut f| {
+            f.write_all(contents.as_bytes())?;
+            f.sync_all()
+        })
+        .and_then(|_| fs::rename(&tmp, path));
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp);
+        return Err(Error::from(e).in_file(path));
+    }
+    Ok(())
+}
+
+/// Returns `Error::NotGenerated` if `path` exists but was not generated by
+/// bpb, that is if [`write_file`] would only replace it with `force` set.
+/// Callers writing several files check them all first, so that nothing is
+/// written if one of them is refused.
+pub fn check_overwritable<P: AsRef<Path>>(path: P) -> Result<(), Error> {
+    let path = path.as_ref();
+    match fs::read_to_string(path) {
+        Ok(ref existing) if is_generated(existing) => Ok(()),
+        Ok(_) => Err(Error::NotGenerated(path.to_owned())),
+        Err(ref e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        // unreadable or not UTF-8: certainly not ours
+        Err(_) => Err(Error::NotGenerated(path.to_owned())),
+    }
+}
+
+/// Compares the file at `path` with `contents`. Returns `None` if they are
+/// identical, otherwise a unified diff turning the file into `contents`. A
+/// missing file is treated as empty.
+pub fn check_file<P: AsRef<Path>>(path: P, contents: &str) -> Result<Option<String>, Error> {
+    let path = path.as_ref();
+
+    let on_disk = match fs::read_to_string(path) {
+        Ok(on_disk) => on_disk,
+        Err(ref e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(Error::from(e).in_file(path)),
+    };
+
+    if on_disk == contents {
+        return Ok(None);
+    }
+
+    let name = path.display().to_string();
+    Ok(Some(
+        TextDiff::from_lines(&on_disk, contents)
+            .unified_diff()
+            .header(&name, &format!("{} (regenerated)", name))
+            .to_string(),
+    ))
+}
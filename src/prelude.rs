@@ -0,0 +1,80 @@
+use format::compare_uses;
+use model::{Struct, Typestate, YesNo};
+
+/// Returns the typestate prelude shared by every builder: the `ToAssign`
+/// trait and its two markers, `Yes` and `No` unless renamed by `typestate`.
+///
+/// Unless disabled, `ToAssign` is sealed, so that no other crate can
+/// implement it and the builders can rely on their typestates being one of
+/// the markers.
+pub fn calculate_prelude(typestate: &Typestate) -> String {
+    let set = typestate.marker(YesNo::Yes);
+    let unset = typestate.marker(YesNo::No);
+    let mut output = String::new();
+
+    if typestate.sealed() {
+        output.push_str("mod private {\n");
+        output.push_str("\tpub trait Sealed {}\n");
+        output.push_str(&format!("\timpl Sealed for super::{} {{}}\n", set));
+        output.push_str(&format!("\timpl Sealed for super::{} {{}}\n", unset));
+        output.push_str("}\n\n");
+
+        output.push_str("/// Implemented by the typestates of the builders.\n");
+        output.push_str("pub trait ToAssign: private::Sealed {}\n\n");
+    } else {
+        output.push_str("/// Implemented by the typestates of the builders.\n");
+        output.push_str("pub trait ToAssign {}\n\n");
+    }
+
+    let marker = |name: &str| {
+        if typestate.enums() {
+            format!("#[derive(Debug, Clone, Copy)]\npub enum {} {{}}\n\n", name)
+        } else {
+            format!(
+                "#[derive(Debug, Clone, Copy)]\npub struct {} {{}}\n\n",
+                name
+            )
+        }
+    };
+
+    output.push_str("/// The typestate of a required field that has been set.\n");
+    output.push_str(&marker(set));
+    output.push_str("/// The typestate of a required field that has not been set yet.\n");
+    output.push_str(&marker(unset));
+
+    output.push_str(&format!("impl ToAssign for {} {{}}\n", set));
+    output.push_str(&format!("impl ToAssign for {} {{}}\n", unset));
+    output
+}
+
+/// Returns the paths `stc` has to import besides its `uses`: the prelude
+/// types if `prelude_path` is set and `PhantomData` if the builder is meant
+/// to be self-contained, that is if `prelude_path` or
+/// `prepend_required_types` is set. Only the paths the builder needs and
+/// does not already import are returned.
+pub fn calculate_uses(stc: &Struct) -> Vec<String> {
+    let mut uses = Vec::new();
+    if stc.fields.iter().all(|f| f.optional()) {
+        return uses;
+    }
+
+    if let Some(ref prelude_path) = stc.prelude_path {
+        let mut names = [
+            "ToAssign",
+            stc.typestate.marker(YesNo::Yes),
+            stc.typestate.marker(YesNo::No),
+        ];
+        names.sort_by(|a, b| compare_uses(a, b));
+        uses.push(format!("{}::{{{}}}", prelude_path, names.join(", ")));
+    }
+    if stc.prelude_path.is_some() || stc.prepend_required_types.unwrap_or_default() {
+        uses.push("std::marker::PhantomData".to_owned());
+    }
+
+    uses.retain(|u| {
+        !stc.uses
+            .iter()
+            .any(|existing| existing.trim_end_matches(';').trim() == u)
+    });
+    uses
+}
@@ -0,0 +1,51 @@
+use model::Struct;
+use schemars::schema_for;
+use serde_json::{json, Value};
+
+// the visibilities `check_visibility` of validate.rs accepts: `private` or
+// what syn parses as one, `pub`, `pub(crate)`, `pub(self)`, `pub(super)`
+// and `pub(in path)`
+const VISIBILITY: &str = r"^\s*(private|pub(\s*\(\s*(crate|self|super|in\s+(::\s*)?[A-Za-z_][A-Za-z0-9_]*(\s*::\s*[A-Za-z_][A-Za-z0-9_]*)*)\s*\))?)\s*$";
+
+/// The JSON Schema of a definition file, generated from [`Struct`] and the
+/// types it holds. The descriptions are their doc comments. Catalogs and
+/// typestate configurations have no schema.
+pub fn schema() -> String {
+    let mut schema = serde_json::to_value(schema_for!(Struct)).unwrap();
+
+    // schemars leaves out the aliases, which unknown properties would
+    // reject
+    if let Some(Value::Object(properties)) = schema.pointer_mut("/$defs/Field/properties") {
+        if let Some(initializer) = properties.get("initializer").cloned() {
+            properties.insert("default".to_owned(), initializer);
+        }
+    }
+
+    // a field taken from the catalog gets its name and type from the
+    // entry, any other must give them
+    if let Some(Value::Object(field)) = schema.pointer_mut("/$defs/Field") {
+        field.insert(
+            "if".to_owned(),
+            json!({ "properties": { "use": { "type": "string" } }, "required": ["use"] }),
+        );
+        field.insert(
+            "else".to_owned(),
+            json!({ "required": ["name", "field_type"] }),
+        );
+    }
+
+    for pointer in &[
+        "/properties/visibility",
+        "/$defs/Field/properties/visibility",
+        "/$defs/ConstructorField/properties/visibility",
+        "/$defs/Finalizer/properties/visibility",
+    ] {
+        if let Some(Value::Object(visibility)) = schema.pointer_mut(pointer) {
+            visibility.insert("pattern".to_owned(), Value::from(VISIBILITY));
+        }
+    }
+
+    let mut schema = serde_json::to_string_pretty(&schema).unwrap();
+    schema.push('\n');
+    schema
+}
@@ -0,0 +1,178 @@
+use error::{Diagnostic, Error};
+use locate::{location_of, without_position, Location, Path, Probe, FOUND};
+use serde::de::{DeserializeOwned, DeserializeSeed};
+use serde_json::Value;
+use std::path::Path as FilePath;
+
+/// The languages definitions, catalogs and typestate configurations can be
+/// written in. Every one of them is read into the same model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Syntax {
+    Json,
+    /// JSON Lines, one definition per line.
+    JsonLines,
+    /// JSON5, which allows comments and trailing commas.
+    Json5,
+    Toml,
+    Yaml,
+    Ron,
+}
+
+impl Syntax {
+    /// The syntax of the file at `path`, chosen by its extension: `.json`,
+    /// `.jsonl`, `.json5` or `.jsonc`, `.toml`, `.yaml` or `.yml`, and
+    /// `.ron`. Returns `None` for any other extension.
+    pub fn from_path<P: AsRef<FilePath>>(path: P) -> Option<Syntax> {
+        let extension = path.as_ref().extension()?.to_str()?;
+        match extension {
+            "json" => Some(Syntax::Json),
+            "jsonl" => Some(Syntax::JsonLines),
+            "json5" | "jsonc" => Some(Syntax::Json5),
+            "toml" => Some(Syntax::Toml),
+            "yaml" | "yml" => Some(Syntax::Yaml),
+            "ron" => Some(Syntax::Ron),
+            _ => None,
+        }
+    }
+
+    /// Whether `text` is read by `serde_json` directly.
+    pub(crate) fn is_json(self) -> bool {
+        self == Syntax::Json || self == Syntax::JsonLines
+    }
+
+    /// Finds where `path` points to in `text`: keys resolve to the position
+    /// of the key itself, indexes to the start of the element. Returns
+    /// `None` if the path does not exist or `text` cannot be parsed.
+    pub(crate) fn locate(self, text: &str, path: &Path) -> Option<Location> {
+        let probe = Probe(&path.0);
+        match self {
+            // JSON is JSON5 too, whose parser reports where tokens start
+            Syntax::Json | Syntax::JsonLines | Syntax::Json5 => {
+                let mut deserializer = json5::Deserializer::from_str(text).ok()?;
+                match probe.deserialize(&mut deserializer) {
+                    Err(json5::Error::Message { msg, location }) if msg == FOUND => {
+                        location.map(|l| Location {
+                            line: l.line,
+                            column: l.column,
+                        })
+                    }
+                    _ => None,
+                }
+            }
+            Syntax::Toml => match probe.deserialize(toml::Deserializer::parse(text).ok()?) {
+                Err(ref e) if e.message() == FOUND => {
+                    e.span().map(|span| location_of(text, span.start))
+                }
+                _ => None,
+            },
+            Syntax::Yaml => match probe.deserialize(serde_yaml::Deserializer::from_str(text)) {
+                // serde_yaml adds the path and the position to the message
+                Err(ref e) if e.to_string().contains(FOUND) => e.location().map(|l| Location {
+                    line: l.line(),
+                    column: l.column(),
+                }),
+                _ => None,
+            },
+            Syntax::Ron => {
+                let mut deserializer = ron::Deserializer::from_str(text).ok()?;
+                match probe
+                    .deserialize(&mut deserializer)
+                    .map_err(|e| deserializer.span_error(e))
+                {
+                    Err(ron::error::SpannedError {
+                        code: ron::Error::Message(ref message),
+                        span,
+                    }) if message == FOUND => Some(Location {
+                        line: span.start.line,
+                        column: span.start.col,
+                    }),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// Parses `text` into a JSON value. Syntax errors are reported as a
+    /// diagnostic with their location.
+    pub(crate) fn to_value(self, text: &str) -> Result<Value, Error> {
+        self.deserialize(text)
+    }
+
+    /// Parses `text` into a `T`. Syntax errors and values of the wrong type
+    /// are reported as a diagnostic with their location.
+    pub(crate) fn deserialize<T: DeserializeOwned>(self, text: &str) -> Result<T, Error> {
+        let (message, location) = match self {
+            Syntax::Json | Syntax::JsonLines => match serde_json::from_str(text) {
+                Ok(value) => return Ok(value),
+                Err(e) => (
+                    without_position(&e.to_string()),
+                    Some(Location {
+                        line: e.line(),
+                        column: e.column(),
+                    }),
+                ),
+            },
+            Syntax::Json5 => match json5::from_str(text) {
+                Ok(value) => return Ok(value),
+                Err(json5::Error::Message { msg, location }) => (
+                    // syntax errors come with a drawing of the offending
+                    // line, keep the explanation below it
+                    msg.lines()
+                        .filter_map(|line| line.trim().strip_prefix("= "))
+                        .next_back()
+                        .map_or(msg.clone(), str::to_owned),
+                    location.map(|l| Location {
+                        line: l.line,
+                        column: l.column,
+                    }),
+                ),
+            },
+            Syntax::Toml => match toml::from_str(text) {
+                Ok(value) => return Ok(value),
+                Err(e) => (
+                    e.message().to_owned(),
+                    e.span().map(|span| location_of(text, span.start)),
+                ),
+            },
+            Syntax::Yaml => match serde_yaml::from_str(text) {
+                Ok(value) => return Ok(value),
+                Err(e) => (
+                    without_path(&without_position(&e.to_string())),
+                    e.location().map(|l| Location {
+                        line: l.line(),
+                        column: l.column(),
+                    }),
+                ),
+            },
+            // values of optional fields are written without `Some`
+            Syntax::Ron => match ron::Options::default()
+                .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+                .from_str(text)
+            {
+                Ok(value) => return Ok(value),
+                Err(e) => (
+                    e.code.to_string(),
+                    Some(Location {
+                        line: e.span.start.line,
+                        column: e.span.start.col,
+                    }),
+                ),
+            },
+        };
+
+        Err(Error::Invalid(vec![Diagnostic {
+            path: Path::default(),
+            message,
+            location,
+        }]))
+    }
+}
+
+// removes the path serde_yaml puts before its messages, such as
+// `fields[0].optional: `: paths have no spaces, unlike the messages
+fn without_path(message: &str) -> String {
+    match message.split_once(": ") {
+        Some((path, rest)) if !path.contains(char::is_whitespace) => rest.to_owned(),
+        _ => message.to_owned(),
+    }
+}
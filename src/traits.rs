@@ -0,0 +1,311 @@
+use error::{Diagnostic, Error};
+use generate::{calculate_getter_type, setter_signature};
+use locate::Path;
+use model::{Catalog, ExtraType, Field, Struct};
+use syn::visit::{self, Visit};
+
+/// The definition of a getter or setter trait, derived from the trait name
+/// and the type of the field it accesses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraitDef {
+    /// The trait name without generics, e.g. `ContainerNameRequired`.
+    pub name: String,
+    /// The generic parameters, e.g. `["'a", "T"]`.
+    pub params: Vec<String>,
+    /// The `where` predicates bounding the generic parameters.
+    pub wheres: Vec<String>,
+    /// The items of the trait, one per line.
+    pub items: Vec<String>,
+}
+
+impl TraitDef {
+    /// Renders the trait declaration.
+    pub fn render(&self) -> String {
+        let mut s = format!("pub trait {}", self.name);
+        if !self.params.is_empty() {
+            s.push_str(&format!("<{}>", self.params.join(", ")));
+        }
+        s.push('\n');
+
+        if !self.wheres.is_empty() {
+            s.push_str("where\n");
+            for w in self.wheres.iter() {
+                s.push_str(&format!("\t{},\n", w));
+            }
+        }
+
+        s.push_str("{\n");
+        for item in self.items.iter() {
+            s.push_str(&format!("\t{}\n", item));
+        }
+        s.push_str("}\n\n");
+        s
+    }
+}
+
+/// Derives the definition of every trait referenced by `trait_get` and
+/// `trait_set` in `stc`, in order of appearance and without duplicates.
+///
+/// A trait name can only be turned into a definition if its generic
+/// arguments are lifetimes and type parameters listed in `extra_types`;
+/// anything else, as well as two different definitions for the same name,
+/// is reported as a diagnostic.
+pub fn collect_traits(stc: &Struct) -> Result<Vec<TraitDef>, Vec<Diagnostic>> {
+    let root = Path::default();
+    let mut collector = Collector::default();
+
+    for (i, cf) in stc.constructor_fields.iter().enumerate() {
+        if let Some(ref trait_get) = cf.trait_get {
+            collector.add(
+                stc,
+                root.key("constructor_fields").index(i).key("trait_get"),
+                trait_get,
+                vec![format!("fn {}(&self) -> {};", cf.name, cf.field_type)],
+            );
+        }
+    }
+
+    for (i, f) in stc.fields.iter().enumerate() {
+        let path = root.key("fields").index(i);
+        for (key, trait_name, items) in field_traits(f) {
+            collector.add(stc, path.key(key), trait_name, items);
+        }
+    }
+
+    collector.finish()
+}
+
+/// Derives the definition of every trait referenced by the entries of
+/// `catalog`, in order of id and without duplicates. Each entry is checked
+/// like a builder field whose generics are the entry's `extra_types`.
+pub fn collect_catalog_traits(catalog: &Catalog) -> Result<Vec<TraitDef>, Vec<Diagnostic>> {
+    let root = Path::default().key("fields");
+    let mut collector = Collector::default();
+
+    for (id, entry) in catalog.fields.iter() {
+        let stc = Struct {
+            extra_types: entry.extra_types.clone(),
+            extra_wheres: entry.extra_wheres.clone(),
+            ..Default::default()
+        };
+        for (key, trait_name, items) in field_traits(&entry.to_field()) {
+            collector.add(&stc, root.key(id).key(key), trait_name, items);
+        }
+    }
+
+    collector.finish()
+}
+
+/// The traits a builder field implements: the key naming each trait, the
+/// trait name and the items of its definition.
+pub(crate) fn field_traits(f: &Field) -> Vec<(&'static str, &str, Vec<String>)> {
+    let mut traits = Vec::new();
+
+    if let Some(ref trait_get) = f.trait_get {
+        traits.push((
+            "trait_get",
+            trait_get.as_str(),
+            vec![format!(
+                "fn {}(&self) -> {};",
+                f.name,
+                calculate_getter_type(f)
+            )],
+        ));
+    }
+
+    if let Some(ref trait_set) = f.trait_set {
+        // the type parameter of a generic setter must not shadow the ones
+        // of the trait
+        let params = split_trait_name(trait_set)
+            .map(|(_, params)| params)
+            .unwrap_or_default();
+        let taken = params.iter().map(|p| p.as_str()).collect::<Vec<_>>();
+        traits.push((
+            "trait_set",
+            trait_set.as_str(),
+            vec![
+                "type O;".to_owned(),
+                format!("fn {} -> Self::O;", setter_signature(f, &taken)),
+            ],
+        ));
+    }
+
+    traits
+}
+
+// accumulates trait definitions, reporting the ones that cannot be derived
+// and the ones defined twice in different ways
+#[derive(Default)]
+struct Collector {
+    traits: Vec<TraitDef>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Collector {
+    // `stc` supplies the generics the trait may use
+    fn add(&mut self, stc: &Struct, path: Path, trait_name: &str, items: Vec<String>) {
+        let declared = |p: &String| stc.extra_types.iter().find(|t| t.name() == p);
+        let (name, names, params) = match split_trait_name(trait_name) {
+            Some((name, names)) if names.iter().all(|p| declared(p).is_some()) => {
+                let params = names
+                    .iter()
+                    .map(|p| declared(p).unwrap().declaration(false))
+                    .collect::<Vec<_>>();
+                (name, names, params)
+            }
+            _ => {
+                self.diagnostics.push(Diagnostic::new(
+                    path,
+                    format!(
+                        "cannot derive a definition for trait `{}`, its generic arguments must be lifetimes or type parameters from `extra_types`",
+                        trait_name
+                    ),
+                ));
+                return;
+            }
+        };
+
+        let def = TraitDef {
+            wheres: wheres_for(stc, &names),
+            name,
+            params,
+            items,
+        };
+
+        match self.traits.iter().find(|t| t.name == def.name) {
+            Some(existing) if *existing != def => self.diagnostics.push(Diagnostic::new(
+                path,
+                format!(
+                    "trait `{}` is already defined differently by another field",
+                    def.name
+                ),
+            )),
+            Some(_) => {}
+            None => self.traits.push(def),
+        }
+    }
+
+    fn finish(self) -> Result<Vec<TraitDef>, Vec<Diagnostic>> {
+        if self.diagnostics.is_empty() {
+            Ok(self.traits)
+        } else {
+            Err(self.diagnostics)
+        }
+    }
+}
+
+/// Renders the definitions of every trait referenced by `stc`.
+///
+/// Traits imported through `uses`, such as the ones of a catalog emitted
+/// into a shared module, are left out. `stc` must have passed `validate`.
+pub fn calculate_traits(stc: &Struct) -> String {
+    collect_traits(stc)
+        .map(|traits| {
+            traits
+                .iter()
+                .filter(|t| !is_imported(stc, &t.name))
+                .map(TraitDef::render)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Renders the definitions of every trait referenced by `catalog`, to be
+/// placed in the shared module named by its `module`.
+///
+/// Problems with the catalog are returned as `Error::Invalid`.
+pub fn calculate_catalog_traits(catalog: &Catalog) -> Result<String, Error> {
+    collect_catalog_traits(catalog)
+        .map(|traits| traits.iter().map(TraitDef::render).collect())
+        .map_err(Error::Invalid)
+}
+
+// whether one of the `uses` of `stc` brings a trait named `name` in scope
+fn is_imported(stc: &Struct, name: &str) -> bool {
+    stc.uses.iter().any(|u| {
+        let u = u.trim_end_matches(';').trim();
+        u == name || u.ends_with(&format!("::{}", name))
+    })
+}
+
+// splits `BlockListRequired<'a, T>` into its name and generic parameters
+pub(crate) fn split_trait_name(trait_name: &str) -> Option<(String, Vec<String>)> {
+    let path: syn::Path = syn::parse_str(trait_name).ok()?;
+    if path.leading_colon.is_some() || path.segments.len() != 1 {
+        return None;
+    }
+
+    let segment = &path.segments[0];
+    let params = match segment.arguments {
+        syn::PathArguments::None => Vec::new(),
+        syn::PathArguments::AngleBracketed(ref args) => {
+            let mut params = Vec::new();
+            for arg in args.args.iter() {
+                match *arg {
+                    syn::GenericArgument::Lifetime(ref l) => params.push(l.to_string()),
+                    syn::GenericArgument::Type(syn::Type::Path(ref tp)) if tp.qself.is_none() => {
+                        params.push(tp.path.get_ident()?.to_string())
+                    }
+                    _ => return None,
+                }
+            }
+            params
+        }
+        syn::PathArguments::Parenthesized(_) => return None,
+    };
+
+    Some((segment.ident.to_string(), params))
+}
+
+// the bounds and `extra_wheres` of `stc` that only refer to the generic
+// parameters named `params`, among the ones of `stc`
+fn wheres_for(stc: &Struct, params: &[String]) -> Vec<String> {
+    let generics: Vec<&str> = stc.extra_types.iter().map(ExtraType::name).collect();
+
+    stc.extra_types
+        .iter()
+        .filter_map(ExtraType::predicate)
+        .chain(stc.extra_wheres.iter().cloned())
+        .filter(|w| {
+            let predicate = match syn::parse_str::<syn::WherePredicate>(w) {
+                Ok(predicate) => predicate,
+                Err(_) => return false,
+            };
+            let mut referenced = Referenced {
+                generics: &generics,
+                found: Vec::new(),
+            };
+            referenced.visit_where_predicate(&predicate);
+            !referenced.found.is_empty() && referenced.found.iter().all(|g| params.contains(g))
+        })
+        .collect()
+}
+
+// collects the generic parameters among `generics` a syntax tree refers to
+struct Referenced<'g> {
+    generics: &'g [&'g str],
+    found: Vec<String>,
+}
+
+impl<'g, 'ast> Visit<'ast> for Referenced<'g> {
+    fn visit_lifetime(&mut self, lifetime: &'ast syn::Lifetime) {
+        self.found(lifetime.to_string());
+    }
+
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+        if path.leading_colon.is_none() {
+            if let Some(first) = path.segments.first() {
+                self.found(first.ident.to_string());
+            }
+        }
+        visit::visit_path(self, path);
+    }
+}
+
+impl<'g> Referenced<'g> {
+    fn found(&mut self, name: String) {
+        if self.generics.contains(&name.as_str()) && !self.found.contains(&name) {
+            self.found.push(name);
+        }
+    }
+}
@@ -0,0 +1,432 @@
+use error::Diagnostic;
+use generate::split_reference;
+use locate::Path;
+use model::{Collection, Field, Naming, Setter, Struct, Typestate, YesNo, PRIVATE};
+use syn::parse::Parse;
+use traits::collect_traits;
+
+/// Checks a `Struct` for problems that would otherwise make the generator
+/// panic or emit code that cannot compile. Every problem is collected; an
+/// empty vector means the definition is valid.
+pub fn validate(stc: &Struct) -> Vec<Diagnostic> {
+    let root = Path::default();
+    let mut diagnostics = Vec::new();
+
+    if stc.name.trim().is_empty() {
+        diagnostics.push(Diagnostic::new(
+            root.key("name"),
+            "the builder name cannot be empty".to_owned(),
+        ));
+    } else {
+        check_ident(&mut diagnostics, &stc.name, root.key("name"));
+    }
+
+    if stc.prelude_path.is_some() && stc.prepend_required_types.unwrap_or_default() {
+        diagnostics.push(Diagnostic::new(
+            root.key("prelude_path"),
+            "`prelude_path` cannot be combined with `prepend_required_types`, the prelude types would be defined twice".to_owned(),
+        ));
+    }
+
+    diagnostics.append(&mut validate_typestate(
+        &stc.typestate,
+        root.key("typestate"),
+    ));
+
+    check_visibility(&mut diagnostics, &stc.visibility, root.key("visibility"));
+    if let Some(ref naming) = stc.naming {
+        check_naming(&mut diagnostics, naming, root.key("naming"));
+    }
+
+    for (i, u) in stc.uses.iter().enumerate() {
+        let u = u.trim().trim_end_matches(';');
+        check_fragment::<syn::UseTree>(&mut diagnostics, u, root.key("uses").index(i), "a use");
+    }
+    for (i, t) in stc.extra_types.iter().enumerate() {
+        check_fragment::<syn::GenericParam>(
+            &mut diagnostics,
+            &t.declaration(true),
+            root.key("extra_types").index(i),
+            "a generic parameter",
+        );
+    }
+    for (i, w) in stc.extra_wheres.iter().enumerate() {
+        check_fragment::<syn::WherePredicate>(
+            &mut diagnostics,
+            w,
+            root.key("extra_wheres").index(i),
+            "a where predicate",
+        );
+    }
+
+    let mut names: Vec<(&str, Path)> = Vec::new();
+    let mut builder_types: Vec<(&str, Path)> = Vec::new();
+
+    for (i, cf) in stc.constructor_fields.iter().enumerate() {
+        let path = root.key("constructor_fields").index(i);
+        check_duplicate(&mut diagnostics, &mut names, &cf.name, path.key("name"));
+        check_ident(&mut diagnostics, &cf.name, path.key("name"));
+        check_visibility(&mut diagnostics, &cf.visibility, path.key("visibility"));
+        check_fragment::<syn::Type>(
+            &mut diagnostics,
+            &cf.field_type,
+            path.key("field_type"),
+            "a type",
+        );
+        if let Some(ref trait_get) = cf.trait_get {
+            check_fragment::<syn::Path>(
+                &mut diagnostics,
+                trait_get,
+                path.key("trait_get"),
+                "a trait",
+            );
+        }
+    }
+
+    for (i, f) in stc.fields.iter().enumerate() {
+        let path = root.key("fields").index(i);
+
+        if let Some(ref id) = f.catalog_entry {
+            diagnostics.push(Diagnostic::new(
+                path.key("use"),
+                format!(
+                    "field uses catalog entry `{}`, but no catalog was given",
+                    id
+                ),
+            ));
+            continue;
+        }
+        if f.name.is_empty() {
+            diagnostics.push(Diagnostic::new(
+                path.clone(),
+                "field has no `name`".to_owned(),
+            ));
+        } else {
+            check_ident(&mut diagnostics, &f.name, path.key("name"));
+        }
+        if let Some(ref builder_type) = f.builder_type {
+            check_ident(&mut diagnostics, builder_type, path.key("builder_type"));
+            check_builder_type(
+                &mut diagnostics,
+                stc,
+                builder_type,
+                path.key("builder_type"),
+            );
+        }
+        if f.field_type.is_empty() {
+            diagnostics.push(Diagnostic::new(
+                path.clone(),
+                format!("field `{}` has no `field_type`", f.name),
+            ));
+        } else {
+            check_fragment::<syn::Type>(
+                &mut diagnostics,
+                &f.field_type,
+                path.key("field_type"),
+                "a type",
+            );
+        }
+        if let Some(ref initializer) = f.initializer {
+            check_fragment::<syn::Expr>(
+                &mut diagnostics,
+                initializer,
+                path.key("initializer"),
+                "an expression",
+            );
+        }
+        for &(key, trait_name) in [("trait_get", &f.trait_get), ("trait_set", &f.trait_set)].iter()
+        {
+            if let Some(ref trait_name) = *trait_name {
+                check_fragment::<syn::Path>(&mut diagnostics, trait_name, path.key(key), "a trait");
+            }
+        }
+
+        check_duplicate(&mut diagnostics, &mut names, &f.name, path.key("name"));
+        check_visibility(&mut diagnostics, &f.visibility, path.key("visibility"));
+        check_setter(&mut diagnostics, f, &path);
+        if f.initializer.is_some() && f.default_trait() {
+            diagnostics.push(Diagnostic::new(
+                path.key("default_trait"),
+                format!(
+                    "field `{}` has both a default value and `default_trait`",
+                    f.name
+                ),
+            ));
+        }
+        if f.collection() && Collection::of(&f.field_type).is_none() {
+            diagnostics.push(Diagnostic::new(
+                path.key("collection"),
+                format!(
+                    "a collection must be a `Vec`, `HashSet`, `HashMap` or `BTreeMap`, but field `{}` has type `{}`",
+                    f.name, f.field_type
+                ),
+            ));
+        }
+
+        if !f.optional() {
+            match f.builder_type {
+                Some(ref bt) => {
+                    if let Some((_, first)) = builder_types.iter().find(|b| b.0 == bt) {
+                        diagnostics.push(Diagnostic::new(
+                            path.key("builder_type"),
+                            format!("builder type `{}` is already used by {}", bt, first),
+                        ));
+                    } else {
+                        builder_types.push((bt, path.key("builder_type")));
+                    }
+                }
+                None => diagnostics.push(Diagnostic::new(
+                    path.clone(),
+                    format!("required field `{}` has no `builder_type`", f.name),
+                )),
+            }
+        }
+
+        match (&f.trait_get, &f.trait_set) {
+            (None, Some(_)) => diagnostics.push(Diagnostic::new(
+                path.key("trait_set"),
+                format!("field `{}` has a `trait_set` but no `trait_get`", f.name),
+            )),
+            (Some(_), None) => diagnostics.push(Diagnostic::new(
+                path.key("trait_get"),
+                format!("field `{}` has a `trait_get` but no `trait_set`", f.name),
+            )),
+            _ => {}
+        }
+    }
+
+    let methods = generated_methods(stc);
+    for (i, fin) in stc.finalizers.iter().enumerate() {
+        let path = root.key("finalizers").index(i);
+        check_ident(&mut diagnostics, &fin.name, path.key("name"));
+        if let Some((_, method)) = methods.iter().find(|m| m.0 == fin.name) {
+            diagnostics.push(Diagnostic::new(
+                path.key("name"),
+                format!("finalizer `{}` has the same name as {}", fin.name, method),
+            ));
+        }
+        if let Some(ref return_type) = fin.return_type {
+            check_fragment::<syn::Type>(
+                &mut diagnostics,
+                return_type,
+                path.key("return_type"),
+                "a type",
+            );
+        }
+        if let Some(ref body) = fin.body {
+            // the body is pasted between the braces of the method
+            if let Err(e) = syn::parse_str::<syn::Block>(&format!("{{\n{}\n}}", body)) {
+                diagnostics.push(Diagnostic::new(
+                    path.key("body"),
+                    format!("`{}` is not a function body: {}", body, e),
+                ));
+            }
+        }
+        check_visibility(&mut diagnostics, &fin.visibility, path.key("visibility"));
+    }
+
+    if stc.emit_traits() {
+        if let Err(mut d) = collect_traits(stc) {
+            diagnostics.append(&mut d);
+        }
+    }
+
+    diagnostics
+}
+
+/// Checks a typestate configuration found at `path`: the markers must be
+/// distinct identifiers.
+pub fn validate_typestate(typestate: &Typestate, path: Path) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for &(key, yes_no) in [("set", YesNo::Yes), ("unset", YesNo::No)].iter() {
+        let marker = typestate.marker(yes_no);
+        if syn::parse_str::<syn::Ident>(marker).is_err() {
+            diagnostics.push(Diagnostic::new(
+                path.key(key),
+                format!("marker `{}` is not an identifier", marker),
+            ));
+        }
+    }
+
+    if typestate.marker(YesNo::Yes) == typestate.marker(YesNo::No) {
+        diagnostics.push(Diagnostic::new(
+            path.key("unset"),
+            format!(
+                "the set and unset markers are both named `{}`",
+                typestate.marker(YesNo::No)
+            ),
+        ));
+    }
+
+    diagnostics
+}
+
+fn check_setter(diagnostics: &mut Vec<Diagnostic>, f: &Field, path: &Path) {
+    let set = [
+        ("setter_into", f.setter_into),
+        ("setter_as_ref", f.setter_as_ref),
+        ("setter_impl_trait", f.setter_impl_trait),
+    ]
+    .iter()
+    .filter(|s| s.1.unwrap_or_default())
+    .map(|s| s.0)
+    .collect::<Vec<_>>();
+
+    if set.len() > 1 {
+        diagnostics.push(Diagnostic::new(
+            path.key(set[1]),
+            format!("`{}` cannot be combined with `{}`", set[1], set[0]),
+        ));
+    }
+    if f.setter() == Setter::AsRef && split_reference(&f.field_type).is_none() {
+        diagnostics.push(Diagnostic::new(
+            path.key("setter_as_ref"),
+            format!(
+                "`setter_as_ref` needs a shared reference type, but field `{}` has type `{}`",
+                f.name, f.field_type
+            ),
+        ));
+    }
+}
+
+// a builder type is a generic parameter of the builder, next to the extra
+// types, and the markers are the types it is set to
+fn check_builder_type(
+    diagnostics: &mut Vec<Diagnostic>,
+    stc: &Struct,
+    builder_type: &str,
+    path: Path,
+) {
+    if stc.extra_types.iter().any(|t| t.name() == builder_type) {
+        diagnostics.push(Diagnostic::new(
+            path,
+            format!("builder type `{}` is already an extra type", builder_type),
+        ));
+    } else if [YesNo::Yes, YesNo::No]
+        .iter()
+        .any(|&yes_no| stc.typestate.marker(yes_no) == builder_type)
+    {
+        diagnostics.push(Diagnostic::new(
+            path,
+            format!("builder type `{}` is also a typestate marker", builder_type),
+        ));
+    }
+}
+
+// the methods generated for the builder, with what they are, which the
+// finalizers must not be named after
+fn generated_methods(stc: &Struct) -> Vec<(String, String)> {
+    let mut methods = vec![("new".to_owned(), "the constructor".to_owned())];
+    for cf in stc.constructor_fields.iter() {
+        methods.push((
+            cf.name.clone(),
+            format!("the getter of constructor field `{}`", cf.name),
+        ));
+    }
+    for f in stc.fields.iter().filter(|f| f.catalog_entry.is_none()) {
+        methods.push((f.name.clone(), format!("the getter of field `{}`", f.name)));
+        methods.push((
+            format!("with_{}", f.name),
+            format!("the setter of field `{}`", f.name),
+        ));
+        if f.collection() {
+            for prefix in ["add_", "extend_"].iter() {
+                methods.push((
+                    format!("{}{}", prefix, f.name),
+                    format!("the setter of field `{}`", f.name),
+                ));
+            }
+        }
+    }
+    methods
+}
+
+fn check_visibility(diagnostics: &mut Vec<Diagnostic>, visibility: &Option<String>, path: Path) {
+    let visibility = match *visibility {
+        Some(ref visibility) if visibility.trim() != PRIVATE => visibility,
+        _ => return,
+    };
+
+    match syn::parse_str::<syn::Visibility>(visibility) {
+        Ok(syn::Visibility::Inherited) | Err(_) => diagnostics.push(Diagnostic::new(
+            path,
+            format!(
+                "`{}` is not a visibility, expected `pub`, `pub(crate)`, `pub(super)` or `{}`",
+                visibility, PRIVATE
+            ),
+        )),
+        Ok(_) => {}
+    }
+}
+
+// `fragment` is pasted into the generated code, so it must parse as a `T`,
+// which is `what`
+fn check_fragment<T: Parse>(
+    diagnostics: &mut Vec<Diagnostic>,
+    fragment: &str,
+    path: Path,
+    what: &str,
+) {
+    if let Err(e) = syn::parse_str::<T>(fragment) {
+        diagnostics.push(Diagnostic::new(
+            path,
+            format!("`{}` is not {}: {}", fragment, what, e),
+        ));
+    }
+}
+
+// raw identifiers are rejected: the generated method names are made by
+// adding to the names, and `with_r#match` is not an identifier
+fn check_ident(diagnostics: &mut Vec<Diagnostic>, name: &str, path: Path) {
+    if name.starts_with("r#") {
+        diagnostics.push(Diagnostic::new(
+            path,
+            format!(
+                "`{}` is not an identifier: raw identifiers are not supported",
+                name
+            ),
+        ));
+        return;
+    }
+    check_fragment::<syn::Ident>(diagnostics, name, path, "an identifier");
+}
+
+// each template must name the field and give an identifier
+fn check_naming(diagnostics: &mut Vec<Diagnostic>, naming: &Naming, path: Path) {
+    let templates = [
+        ("builder_type", naming.builder_type()),
+        ("trait_get_required", naming.trait_get_required()),
+        ("trait_get_optional", naming.trait_get_optional()),
+        ("trait_set", naming.trait_set()),
+    ];
+    for &(key, template) in templates.iter() {
+        if !template.contains("{}")
+            || syn::parse_str::<syn::Ident>(&template.replace("{}", "Field")).is_err()
+        {
+            diagnostics.push(Diagnostic::new(
+                path.key(key),
+                format!(
+                    "`{}` is not a naming template, expected an identifier containing `{{}}`",
+                    template
+                ),
+            ));
+        }
+    }
+}
+
+fn check_duplicate<'a>(
+    diagnostics: &mut Vec<Diagnostic>,
+    seen: &mut Vec<(&'a str, Path)>,
+    name: &'a str,
+    path: Path,
+) {
+    if let Some((_, first)) = seen.iter().find(|s| s.0 == name) {
+        diagnostics.push(Diagnostic::new(
+            path,
+            format!("field name `{}` is already used by {}", name, first),
+        ));
+    } else {
+        seen.push((name, path));
+    }
+}
@@ -0,0 +1,88 @@
+//! Generates a directory of definitions kept with their catalog and
+//! typestate configuration, as a build script would.
+
+extern crate bpb;
+
+mod common;
+
+use bpb::build::{generate_dir_with, Config};
+use common::dir;
+use std::fs;
+use std::path::PathBuf;
+
+const DEFINITION: &str = r#"{
+    "name": "GetBlobBuilder",
+    "extra_types": ["'a"],
+    "fields": [
+        { "use": "container_name" },
+        { "use": "timeout" }
+    ]
+}"#;
+
+#[test]
+fn catalog_and_typestate_are_not_definitions() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let dir = dir("catalog_and_typestate_are_not_definitions");
+    let input_dir = dir.join("builders");
+    let out_dir = dir.join("out");
+    fs::create_dir(&input_dir).unwrap();
+
+    fs::copy(root.join("catalog.json"), input_dir.join("catalog.json")).unwrap();
+    fs::write(input_dir.join("typestate.toml"), "set = \"Assigned\"\n").unwrap();
+    fs::write(input_dir.join("get_blob_builder.json"), DEFINITION).unwrap();
+
+    let config = Config {
+        catalog: Some(input_dir.join("catalog.json")),
+        typestate: Some(input_dir.join("typestate.toml")),
+    };
+    let generated = generate_dir_with(&input_dir, &out_dir, &config).unwrap();
+    assert_eq!(generated, vec![out_dir.join("get_blob_builder.rs")]);
+
+    let output = fs::read_to_string(&generated[0]).unwrap();
+    assert!(output.contains("use crate::traits::ContainerNameRequired;"));
+    assert!(output.contains("GetBlobBuilder<'a, Assigned>"));
+}
+
+#[test]
+fn definitions_are_generated_without_a_config() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let dir = dir("definitions_are_generated_without_a_config");
+    fs::create_dir(dir.join("builders")).unwrap();
+    fs::copy(root.join("simple.json"), dir.join("builders/simple.json")).unwrap();
+
+    let generated = bpb::build::generate_dir(dir.join("builders"), dir.join("out")).unwrap();
+    assert_eq!(generated.len(), 1);
+    assert!(bpb::is_generated(
+        &fs::read_to_string(&generated[0]).unwrap()
+    ));
+}
+
+#[test]
+fn builders_written_to_the_same_file_are_reported() {
+    let dir = dir("builders_written_to_the_same_file_are_reported");
+    let input_dir = dir.join("builders");
+    let out_dir = dir.join("out");
+    fs::create_dir(&input_dir).unwrap();
+    fs::write(
+        input_dir.join("a.json"),
+        r#"{ "name": "GetBlobBuilder", "fields": [] }"#,
+    )
+    .unwrap();
+    fs::write(
+        input_dir.join("b.json"),
+        r#"{ "name": "GetBLOBBuilder", "fields": [] }"#,
+    )
+    .unwrap();
+
+    let e = bpb::build::generate_dir(&input_dir, &out_dir).unwrap_err();
+    assert_eq!(
+        e.to_string(),
+        format!(
+            "{}: both `GetBlobBuilder` from {} and `GetBLOBBuilder` from {} would be written to it",
+            out_dir.join("get_blob_builder.rs").display(),
+            input_dir.join("a.json").display(),
+            input_dir.join("b.json").display()
+        )
+    );
+    assert!(!out_dir.exists());
+}
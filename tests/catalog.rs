@@ -0,0 +1,159 @@
+//! Resolves definitions against the catalog at the root of the repository,
+//! through the library and through the command line.
+
+extern crate bpb;
+extern crate serde_json;
+
+mod common;
+
+use common::dir;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn catalog() -> bpb::Catalog {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    bpb::load_catalog(root.join("catalog.json")).unwrap()
+}
+
+// resolves the definition of `fields` and returns its diagnostics
+fn resolve(fields: &str) -> (bpb::Struct, Vec<String>) {
+    let text = format!(
+        r#"{{ "name": "GetBlobBuilder", "extra_types": ["'a"], "fields": {} }}"#,
+        fields
+    );
+    let mut stc: bpb::Struct = serde_json::from_str(&text).unwrap();
+    let diagnostics = bpb::resolve(&mut stc, &catalog());
+    (stc, diagnostics.iter().map(|d| d.to_string()).collect())
+}
+
+#[test]
+fn unknown_entries_are_reported() {
+    let (_, diagnostics) = resolve(r#"[{ "use": "container_nam" }]"#);
+    assert_eq!(
+        diagnostics,
+        vec!["fields[0].use: unknown catalog entry `container_nam`"]
+    );
+}
+
+#[test]
+fn conflicting_keys_are_reported() {
+    let (_, diagnostics) = resolve(r#"[{ "use": "timeout", "field_type": "u32" }]"#);
+    assert_eq!(
+        diagnostics,
+        vec!["fields[0].field_type: conflicts with catalog entry `timeout`, which sets `field_type` to `u64`"]
+    );
+}
+
+#[test]
+fn optional_conflicts_both_ways() {
+    let (_, diagnostics) = resolve(
+        r#"[
+            { "use": "container_name", "optional": true },
+            { "use": "timeout", "optional": false }
+        ]"#,
+    );
+    assert_eq!(
+        diagnostics,
+        vec![
+            "fields[0].optional: conflicts with catalog entry `container_name`, which sets `optional` to `false`",
+            "fields[1].optional: conflicts with catalog entry `timeout`, which sets `optional` to `true`",
+        ]
+    );
+}
+
+#[test]
+fn left_out_keys_are_taken_from_the_entry() {
+    let (stc, diagnostics) = resolve(r#"[{ "use": "timeout" }]"#);
+    assert!(diagnostics.is_empty());
+    assert_eq!(stc.fields[0].name, "timeout");
+    assert_eq!(stc.fields[0].field_type, "u64");
+    assert!(stc.fields[0].optional());
+    assert_eq!(stc.fields[0].trait_set.as_deref(), Some("TimeoutSupport"));
+}
+
+#[test]
+fn traits_defined_differently_are_reported() {
+    let (_, diagnostics) = resolve(
+        r#"[{
+            "name": "retries",
+            "field_type": "u32",
+            "optional": true,
+            "trait_get": "TimeoutOption",
+            "trait_set": "RetriesSupport"
+        }]"#,
+    );
+    assert_eq!(
+        diagnostics,
+        vec!["fields[0].trait_get: trait `TimeoutOption` is defined differently by catalog entry `timeout`"]
+    );
+}
+
+#[test]
+fn catalog_traits_are_imported_from_the_module() {
+    let (stc, diagnostics) = resolve(
+        r#"[
+            { "use": "container_name" },
+            { "use": "timeout" },
+            { "name": "prefix", "field_type": "&'a str", "optional": true }
+        ]"#,
+    );
+    assert!(diagnostics.is_empty());
+    assert_eq!(
+        stc.uses,
+        vec![
+            "crate::traits::ContainerNameRequired",
+            "crate::traits::ContainerNameSupport",
+            "crate::traits::TimeoutOption",
+            "crate::traits::TimeoutSupport",
+        ]
+    );
+
+    let output = bpb::generate(&stc).unwrap();
+    assert!(output.contains("use crate::traits::TimeoutSupport;"));
+    assert!(!output.contains("pub trait TimeoutSupport"));
+}
+
+#[test]
+fn command_line_generates_builders_and_catalog_traits() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let dir = dir("catalog_cli");
+    fs::write(
+        dir.join("get_blob_builder.json"),
+        r#"{
+            "name": "GetBlobBuilder",
+            "extra_types": ["'a"],
+            "fields": [{ "use": "container_name" }, { "use": "timeout" }]
+        }"#,
+    )
+    .unwrap();
+
+    let run = |check: bool| {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_bpb"));
+        if check {
+            command.arg("--check");
+        }
+        command
+            .arg("--out-dir")
+            .arg(&dir)
+            .arg("--catalog")
+            .arg(root.join("catalog.json"))
+            .arg("--catalog-traits")
+            .arg(dir.join("traits.rs"))
+            .arg(dir.join("get_blob_builder.json"))
+            .output()
+            .unwrap()
+            .status
+    };
+
+    assert!(run(false).success());
+    let builder = fs::read_to_string(dir.join("get_blob_builder.rs")).unwrap();
+    assert!(builder.contains("use crate::traits::ContainerNameRequired;"));
+    let traits = fs::read_to_string(dir.join("traits.rs")).unwrap();
+    assert!(traits.contains("pub trait TimeoutSupport"));
+    assert!(traits.contains("pub trait ClientRequestIdOption<'a>"));
+
+    assert!(run(true).success());
+    fs::write(dir.join("traits.rs"), traits.replace("Timeout", "Deadline")).unwrap();
+    assert!(!run(true).success());
+}
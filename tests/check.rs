@@ -0,0 +1,102 @@
+//! `bpb --check` compares the files it would write with the ones on disk,
+//! printing a diff and exiting with a non-zero status if any differs.
+
+extern crate bpb;
+
+mod common;
+
+use common::dir;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+fn bpb(args: &[&Path]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_bpb"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+// the check failed, reporting `file` as out of date with a diff
+fn assert_out_of_date(output: &Output, file: &Path) {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{}", stderr);
+    assert!(
+        stderr.contains(&format!("{}: out of date", file.display())),
+        "{}",
+        stderr
+    );
+    assert!(
+        stdout.contains(&format!("+++ {} (regenerated)", file.display())),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn stale_files_are_reported_with_a_diff() {
+    let dir = dir("stale");
+    let out = dir.join("simple.rs");
+    let simple = fixture("simple.json");
+    let check = [Path::new("--check"), Path::new("--against"), &out, &simple];
+
+    assert!(bpb(&[Path::new("--out"), &out, &simple]).status.success());
+    let output = bpb(&check);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let generated = fs::read_to_string(&out).unwrap();
+    fs::write(&out, generated.replace("SaratogaBuilder", "Saratoga")).unwrap();
+    let output = bpb(&check);
+    assert_out_of_date(&output, &out);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("+pub struct SaratogaBuilder"));
+}
+
+#[test]
+fn missing_files_are_reported_with_a_diff() {
+    let dir = dir("missing");
+    let out = dir.join("simple.rs");
+
+    let output = bpb(&[
+        Path::new("--check"),
+        Path::new("--against"),
+        &out,
+        &fixture("simple.json"),
+    ]);
+    assert_out_of_date(&output, &out);
+    assert!(!out.exists());
+}
+
+#[test]
+fn every_builder_of_an_out_dir_is_checked() {
+    let dir = dir("out_dir");
+    let definitions = fixture("shared_prelude.json");
+    let check = [
+        Path::new("--check"),
+        Path::new("--out-dir"),
+        &dir,
+        &definitions,
+    ];
+    let get_queue = dir.join("get_queue_builder.rs");
+    let put_message = dir.join("put_message_builder.rs");
+
+    assert!(bpb(&[Path::new("--out-dir"), &dir, &definitions])
+        .status
+        .success());
+    assert!(bpb(&check).status.success());
+
+    let generated = fs::read_to_string(&put_message).unwrap();
+    fs::write(&put_message, "").unwrap();
+    assert_out_of_date(&bpb(&check), &put_message);
+    fs::write(&put_message, generated).unwrap();
+
+    fs::remove_file(&get_queue).unwrap();
+    assert_out_of_date(&bpb(&check), &get_queue);
+}
@@ -0,0 +1,26 @@
+//! Helpers shared by the integration tests, each of which uses only some of
+//! them.
+
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::PathBuf;
+
+/// An empty directory of its own for each test, under a directory named
+/// after the test file.
+pub fn dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join(env!("CARGO_CRATE_NAME"))
+        .join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// The diagnostics of `e`, which must be `Error::Invalid`, as printed.
+pub fn messages(e: bpb::Error) -> Vec<String> {
+    match e {
+        bpb::Error::Invalid(diagnostics) => diagnostics.iter().map(|d| d.to_string()).collect(),
+        other => panic!("expected diagnostics, got {:?}", other),
+    }
+}
@@ -0,0 +1,13 @@
+//! Compiles the snapshots in `tests/snapshots` together with the stubs in
+//! `tests/stubs` or the generated prelude: the `pass` cases use the
+//! builders the way they are meant to be used, the `fail` cases check that
+//! the typestates reject misuse.
+
+extern crate trybuild;
+
+#[test]
+fn generated_builders() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/pass/*.rs");
+    t.compile_fail("tests/ui/fail/*.rs");
+}
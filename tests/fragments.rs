@@ -0,0 +1,95 @@
+//! Names, types, traits, generic parameters, where predicates,
+//! expressions, finalizer bodies and uses are parsed when the definition is
+//! loaded, and reported where they are written.
+
+extern crate bpb;
+
+mod common;
+
+use common::messages;
+
+#[test]
+fn every_fragment_is_parsed() {
+    let text = r#"{
+    "name": "GetBlobBuilder",
+    "uses": ["std::borrow::Borrow;", "std::{io"],
+    "extra_types": ["'a", { "type": "T", "bounds": ["Clone<"] }],
+    "extra_wheres": ["T: Borrow<[u8]", "T: 'a"],
+    "constructor_fields": [
+        { "name": "client", "field_type": "&'a Client", "trait_get": "Client Required" }
+    ],
+    "fields": [
+        {
+            "name": "timeout",
+            "field_type": "Vec<u64",
+            "optional": true,
+            "initializer": "vec![1,",
+            "trait_get": "TimeoutOption",
+            "trait_set": "TimeoutSupport<"
+        }
+    ],
+    "finalizers": [{ "name": "finalize", "return_type": "Result<()" }]
+}"#;
+    // syn's own explanation follows and changes between its versions
+    let messages = messages(bpb::from_str(text).unwrap_err());
+    let expected = [
+        "3:38: uses[1]: `std::{io` is not a use:",
+        "4:27: extra_types[1]: `T: Clone<` is not a generic parameter:",
+        "5:22: extra_wheres[0]: `T: Borrow<[u8]` is not a where predicate:",
+        "7:57: constructor_fields[0].trait_get: `Client Required` is not a trait:",
+        "12:13: fields[0].field_type: `Vec<u64` is not a type:",
+        "14:13: fields[0].initializer: `vec![1,` is not an expression:",
+        "16:13: fields[0].trait_set: `TimeoutSupport<` is not a trait:",
+        "19:42: finalizers[0].return_type: `Result<()` is not a type:",
+    ];
+    assert_eq!(messages.len(), expected.len(), "{:?}", messages);
+    for (message, expected) in messages.iter().zip(expected.iter()) {
+        assert!(message.starts_with(expected), "{}", message);
+    }
+}
+
+#[test]
+fn names_and_bodies_are_parsed() {
+    let text = r#"{
+    "name": "Get Blob Builder",
+    "constructor_fields": [{ "name": "self", "field_type": "u64" }],
+    "fields": [
+        { "name": "match", "field_type": "u64", "optional": true },
+        { "name": "lease_id", "field_type": "u64", "builder_type": "Lease-Set" }
+    ],
+    "finalizers": [{ "name": "fn", "body": "let x = ;" }]
+}"#;
+    let messages = messages(bpb::from_str(text).unwrap_err());
+    let expected = [
+        "2:5: name: `Get Blob Builder` is not an identifier:",
+        "3:30: constructor_fields[0].name: `self` is not an identifier:",
+        "5:11: fields[0].name: `match` is not an identifier:",
+        "6:52: fields[1].builder_type: `Lease-Set` is not an identifier:",
+        "8:22: finalizers[0].name: `fn` is not an identifier:",
+        "8:36: finalizers[0].body: `let x = ;` is not a function body:",
+    ];
+    assert_eq!(messages.len(), expected.len(), "{:?}", messages);
+    for (message, expected) in messages.iter().zip(expected.iter()) {
+        assert!(message.starts_with(expected), "{}", message);
+    }
+}
+
+#[test]
+fn raw_identifiers_are_rejected() {
+    // `r#match` is an identifier, but `with_r#match` is not
+    let text = r#"{
+    "name": "r#GetBlobBuilder",
+    "fields": [
+        { "name": "r#match", "field_type": "u64", "optional": true },
+        { "name": "lease_id", "field_type": "u64", "builder_type": "r#LeaseSet" }
+    ]
+}"#;
+    assert_eq!(
+        messages(bpb::from_str(text).unwrap_err()),
+        vec![
+            "2:5: name: `r#GetBlobBuilder` is not an identifier: raw identifiers are not supported",
+            "4:11: fields[0].name: `r#match` is not an identifier: raw identifiers are not supported",
+            "5:52: fields[1].builder_type: `r#LeaseSet` is not an identifier: raw identifiers are not supported",
+        ]
+    );
+}
@@ -0,0 +1,239 @@
+//! Property tests for the generic parameter lists of the generated code:
+//! whatever the mix of lifetimes, type and const parameters, bounds and
+//! required fields, the builder is declared with every parameter, lifetimes
+//! first, and every impl names the builder with the same number of
+//! arguments, in the same order.
+
+extern crate bpb;
+extern crate proptest;
+extern crate syn;
+
+use bpb::{ConstructorField, ExtraType, Field, Struct};
+use proptest::prelude::*;
+
+const LIFETIMES: [&str; 3] = ["'a", "'b: 'a", "'c"];
+const TYPES: [&str; 3] = ["T", "U: Clone + Default", "V: Into<u8>"];
+const CONSTS: [&str; 2] = ["const N: usize", "const M: bool"];
+
+// a definition with the given generics in shuffled order, `required`
+// required fields and `optional` optional ones, with or without traits
+fn definition() -> impl Strategy<Value = Struct> {
+    (
+        (0..=LIFETIMES.len(), 0..=TYPES.len(), 0..=CONSTS.len()),
+        0usize..4,
+        0usize..3,
+        0usize..3,
+        any::<bool>(),
+        any::<bool>(),
+    )
+        .prop_flat_map(
+            |((lifetimes, types, consts), required, optional, constructor, traits, in_where)| {
+                let extra_types = LIFETIMES[..lifetimes]
+                    .iter()
+                    .chain(TYPES[..types].iter())
+                    .chain(CONSTS[..consts].iter())
+                    .map(|t| ExtraType::from(*t))
+                    .collect::<Vec<_>>();
+                (
+                    Just(extra_types).prop_shuffle(),
+                    Just((required, optional, constructor, traits, in_where)),
+                )
+            },
+        )
+        .prop_map(
+            |(extra_types, (required, optional, constructor, traits, in_where))| {
+                let field = |name: String, optional: bool| Field {
+                    trait_get: if traits {
+                        Some(format!("{}Get", name))
+                    } else {
+                        None
+                    },
+                    trait_set: if traits {
+                        Some(format!("{}Set", name))
+                    } else {
+                        None
+                    },
+                    builder_type: if optional {
+                        None
+                    } else {
+                        Some(format!("{}State", name))
+                    },
+                    name,
+                    field_type: "u8".to_owned(),
+                    optional: Some(optional),
+                    ..Default::default()
+                };
+
+                Struct {
+                    name: "Builder".to_owned(),
+                    extra_types,
+                    bounds_in_where: Some(in_where),
+                    constructor_fields: (0..constructor)
+                        .map(|i| ConstructorField {
+                            name: format!("c{}", i),
+                            field_type: "u8".to_owned(),
+                            ..Default::default()
+                        })
+                        .collect(),
+                    fields: (0..required)
+                        .map(|i| field(format!("r{}", i), false))
+                        .chain((0..optional).map(|i| field(format!("o{}", i), true)))
+                        .collect(),
+                    ..Default::default()
+                }
+            },
+        )
+}
+
+fn is_lifetime(t: &str) -> bool {
+    t.starts_with('\'')
+}
+
+// the names of the extra types of `stc`, lifetimes first, followed by
+// `typestates`
+fn expected(stc: &Struct, typestates: Vec<String>) -> Vec<String> {
+    let lifetimes = stc.extra_types.iter().filter(|t| t.is_lifetime());
+    let types = stc.extra_types.iter().filter(|t| !t.is_lifetime());
+    lifetimes
+        .chain(types)
+        .map(|t| t.name().to_owned())
+        .chain(typestates)
+        .collect()
+}
+
+fn param_name(p: &syn::GenericParam) -> String {
+    match *p {
+        syn::GenericParam::Lifetime(ref l) => l.lifetime.to_string(),
+        syn::GenericParam::Type(ref t) => t.ident.to_string(),
+        syn::GenericParam::Const(ref c) => c.ident.to_string(),
+    }
+}
+
+// the generic arguments of `ty` if it names the builder
+fn builder_arguments(ty: &syn::Type) -> Option<Vec<String>> {
+    let path = match *ty {
+        syn::Type::Path(ref tp) => &tp.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != "Builder" {
+        return None;
+    }
+
+    Some(match segment.arguments {
+        syn::PathArguments::AngleBracketed(ref args) => args
+            .args
+            .iter()
+            .map(|a| match *a {
+                syn::GenericArgument::Lifetime(ref l) => l.to_string(),
+                syn::GenericArgument::Type(syn::Type::Path(ref tp)) => {
+                    tp.path.get_ident().unwrap().to_string()
+                }
+                _ => panic!("unexpected generic argument"),
+            })
+            .collect(),
+        _ => Vec::new(),
+    })
+}
+
+proptest! {
+    #[test]
+    fn generic_parameters_line_up(stc in definition()) {
+        let code = bpb::generate_unformatted(&stc).unwrap();
+        let file: syn::File = syn::parse_str(&code).unwrap();
+
+        let lifetimes = stc.extra_types.iter().filter(|t| t.is_lifetime()).count();
+        let types = stc.extra_types.len() - lifetimes;
+        let required = stc.fields.iter().filter(|f| !f.optional()).count();
+        let arity = lifetimes + types + required;
+
+        let mut impls = 0;
+        for item in file.items.iter() {
+            match *item {
+                syn::Item::Struct(ref s) => {
+                    let params = s
+                        .generics
+                        .params
+                        .iter()
+                        .map(param_name)
+                        .collect::<Vec<_>>();
+                    let builder_types = stc
+                        .fields
+                        .iter()
+                        .filter_map(|f| f.builder_type.clone())
+                        .collect();
+                    prop_assert_eq!(params, expected(&stc, builder_types));
+
+                    // the bounds are declared inline or in the where clause
+                    let bounded = stc
+                        .extra_types
+                        .iter()
+                        .filter(|t| t.predicate().is_some())
+                        .map(|t| t.name().to_owned())
+                        .collect::<Vec<_>>();
+                    let mut inline = s
+                        .generics
+                        .params
+                        .iter()
+                        .filter(|p| match **p {
+                            syn::GenericParam::Lifetime(ref l) => !l.bounds.is_empty(),
+                            syn::GenericParam::Type(ref t) => !t.bounds.is_empty(),
+                            _ => false,
+                        })
+                        .map(param_name)
+                        .collect::<Vec<_>>();
+                    let mut expected_inline = if stc.bounds_in_where() { Vec::new() } else { bounded };
+                    inline.sort();
+                    expected_inline.sort();
+                    prop_assert_eq!(inline, expected_inline);
+                }
+                syn::Item::Impl(ref i) => {
+                    let args = match builder_arguments(&i.self_ty) {
+                        Some(args) => args,
+                        None => continue,
+                    };
+                    impls += 1;
+                    prop_assert_eq!(args.len(), arity, "in an impl for Builder<{:?}>", args);
+
+                    // the extra types come first, lifetimes leading, and
+                    // every typestate is a marker or a parameter of the impl
+                    let declared = i
+                        .generics
+                        .params
+                        .iter()
+                        .map(param_name)
+                        .collect::<Vec<_>>();
+                    prop_assert!(args[..lifetimes].iter().all(|a| is_lifetime(a)));
+                    prop_assert!(args[lifetimes..lifetimes + types].iter().all(|a| !is_lifetime(a)));
+                    for arg in args.iter() {
+                        prop_assert!(
+                            arg == "Yes" || arg == "No" || declared.contains(arg),
+                            "`{}` is not declared in the impl for Builder<{:?}>", arg, args
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+        prop_assert!(impls > 0);
+    }
+
+    #[test]
+    fn constructor_starts_with_every_field_unset(stc in definition()) {
+        let code = bpb::generate_unformatted(&stc).unwrap();
+        let file: syn::File = syn::parse_str(&code).unwrap();
+
+        let constructor = file.items.iter().find_map(|item| match *item {
+            syn::Item::Impl(ref i) => i.items.iter().find_map(|ii| match *ii {
+                syn::ImplItem::Fn(ref f) if f.sig.ident == "new" => Some(i),
+                _ => None,
+            }),
+            _ => None,
+        });
+        let constructor = constructor.unwrap();
+        let args = builder_arguments(&constructor.self_ty).unwrap();
+        let required = stc.fields.iter().filter(|f| !f.optional()).count();
+
+        prop_assert_eq!(args, expected(&stc, vec!["No".to_owned(); required]));
+    }
+}
@@ -0,0 +1,128 @@
+//! Definitions relying on a naming convention generate the same builders
+//! as the ones spelling every name out.
+
+extern crate bpb;
+
+use std::path::PathBuf;
+
+#[test]
+fn convention_matches_spelled_out_names() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let expected = bpb::load(root.join("tests/fixtures/put_block_list_builder.json")).unwrap();
+
+    let stc = bpb::load(root.join("tests/naming/put_block_list_builder.json")).unwrap();
+    assert_eq!(
+        bpb::generate(&stc).unwrap(),
+        bpb::generate(&expected).unwrap()
+    );
+}
+
+#[test]
+fn templates_can_be_changed_and_names_overridden() {
+    let stc = bpb::from_str(
+        r#"{
+            "name": "GetBlobBuilder",
+            "extra_types": ["'a", "P"],
+            "naming": { "builder_type": "Has{}", "traits": false },
+            "fields": [
+                { "name": "blob_path", "field_type": "P" },
+                { "name": "lease_id", "field_type": "&'a str", "builder_type": "LeaseSet" },
+                { "name": "timeout", "field_type": "u64", "optional": true }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    let names = stc
+        .fields
+        .iter()
+        .map(|f| (f.builder_type.as_deref(), f.trait_get.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec![
+            (Some("HasBlobPath"), None),
+            (Some("LeaseSet"), None),
+            (None, None)
+        ]
+    );
+}
+
+#[test]
+fn trait_arguments_are_the_parameters_in_the_field_type() {
+    let mut stc = bpb::from_str(
+        r#"{
+            "name": "CopyBlobBuilder",
+            "extra_types": ["'a", "'b", "P", "Q"],
+            "fields": [{ "name": "source_path", "field_type": "&'b Option<Q>", "optional": true }]
+        }"#,
+    )
+    .unwrap();
+    stc.naming = Some(bpb::Naming::default());
+    bpb::apply_naming(&mut stc);
+
+    assert_eq!(
+        stc.fields[0].trait_get.as_deref(),
+        Some("SourcePathOption<'b, Q>")
+    );
+    assert_eq!(
+        stc.fields[0].trait_set.as_deref(),
+        Some("SourcePathSupport<'b, Q>")
+    );
+}
+
+#[test]
+fn templates_must_name_the_field() {
+    let e = bpb::from_str(
+        r#"{
+            "name": "GetBlobBuilder",
+            "naming": { "trait_set": "Support" },
+            "fields": []
+        }"#,
+    )
+    .unwrap_err();
+    match e {
+        bpb::Error::Invalid(diagnostics) => assert_eq!(
+            diagnostics[0].to_string(),
+            "3:25: naming.trait_set: `Support` is not a naming template, expected an identifier containing `{}`"
+        ),
+        other => panic!("expected diagnostics, got {:?}", other),
+    }
+}
+
+#[test]
+fn fields_can_opt_out_of_the_traits() {
+    let stc = bpb::from_str(
+        r#"{
+            "name": "GetBlobBuilder",
+            "extra_types": ["'a"],
+            "naming": {},
+            "constructor_fields": [
+                { "name": "client", "field_type": "&'a Client", "traits": false }
+            ],
+            "fields": [
+                { "name": "container_name", "field_type": "&'a str" },
+                { "name": "timeout", "field_type": "u64", "optional": true, "traits": false }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(stc.constructor_fields[0].trait_get, None);
+    let names = stc
+        .fields
+        .iter()
+        .map(|f| (f.builder_type.as_deref(), f.trait_get.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec![
+            (Some("ContainerNameSet"), Some("ContainerNameRequired<'a>")),
+            (None, None)
+        ]
+    );
+
+    let output = bpb::generate(&stc).unwrap();
+    assert!(output.contains("pub fn client(&self) -> &'a Client"));
+    assert!(output.contains("pub fn with_timeout(self, timeout: u64) -> Self"));
+}
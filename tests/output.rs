@@ -0,0 +1,149 @@
+//! Writes generated files over hand-written and generated ones, as the
+//! command line tool does, which writes nothing if two builders would go
+//! to the same file or if one of the files is hand written.
+
+extern crate bpb;
+
+mod common;
+
+use common::dir;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+// nothing but the files of the test is left in `dir`
+fn assert_files(dir: &Path, expected: &[&str]) {
+    let mut files: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    assert_eq!(files, expected);
+}
+
+#[test]
+fn hand_written_files_are_not_overwritten() {
+    let dir = dir("hand_written");
+    let path = dir.join("builder.rs");
+    fs::write(&path, "pub struct Mine;\n").unwrap();
+
+    match bpb::write_file(&path, &bpb::render(&["pub struct Builder;\n"]), false) {
+        Err(bpb::Error::NotGenerated(ref p)) if *p == path => {}
+        other => panic!("expected NotGenerated, got {:?}", other),
+    }
+    assert_eq!(fs::read_to_string(&path).unwrap(), "pub struct Mine;\n");
+    assert_files(&dir, &["builder.rs"]);
+}
+
+#[test]
+fn generated_files_are_overwritten() {
+    let dir = dir("generated");
+    let path = dir.join("builder.rs");
+    fs::write(&path, bpb::render(&["pub struct Old;\n"])).unwrap();
+
+    let contents = bpb::render(&["pub struct Builder;\n"]);
+    bpb::write_file(&path, &contents, false).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+    assert!(bpb::is_generated(&contents));
+    assert_files(&dir, &["builder.rs"]);
+}
+
+#[test]
+fn hand_written_files_are_overwritten_with_force() {
+    let dir = dir("forced");
+    let path = dir.join("builder.rs");
+    fs::write(&path, "pub struct Mine;\n").unwrap();
+    assert!(!bpb::is_generated("pub struct Mine;\n"));
+
+    let contents = bpb::render(&["pub struct Builder;\n"]);
+    bpb::write_file(&path, &contents, true).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+    assert_files(&dir, &["builder.rs"]);
+}
+
+#[test]
+fn new_files_are_written() {
+    let dir = dir("new");
+    let path = dir.join("builder.rs");
+
+    let contents = bpb::render(&["pub struct Builder;\n"]);
+    bpb::write_file(&path, &contents, false).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+    assert_files(&dir, &["builder.rs"]);
+}
+
+#[test]
+fn failed_writes_leave_no_temporary_file() {
+    let dir = dir("failed");
+    let path = dir.join("builder.rs");
+    fs::create_dir(&path).unwrap();
+
+    let contents = bpb::render(&["pub struct Builder;\n"]);
+    assert!(bpb::write_file(&path, &contents, true).is_err());
+    assert_files(&dir, &["builder.rs"]);
+}
+
+#[test]
+fn builders_written_to_the_same_file_are_reported() {
+    let dir = dir("clash");
+    let definitions = dir.join("builders.json");
+    fs::write(
+        &definitions,
+        r#"[
+            { "name": "GetBlobBuilder", "fields": [] },
+            { "name": "GetBLOBBuilder", "fields": [] }
+        ]"#,
+    )
+    .unwrap();
+    let out_dir = dir.join("out");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bpb"))
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .arg(&definitions)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        format!(
+            "{}: both `GetBlobBuilder` from {1} and `GetBLOBBuilder` from {1} would be written to it\n",
+            out_dir.join("get_blob_builder.rs").display(),
+            definitions.display()
+        )
+    );
+    assert!(!out_dir.exists());
+}
+
+#[test]
+fn nothing_is_written_if_one_file_is_hand_written() {
+    let dir = dir("partly_hand_written");
+    let definitions = dir.join("builders.json");
+    fs::write(
+        &definitions,
+        r#"[
+            { "name": "GetBlobBuilder", "fields": [] },
+            { "name": "PutBlobBuilder", "fields": [] },
+            { "name": "DeleteBlobBuilder", "fields": [] }
+        ]"#,
+    )
+    .unwrap();
+    let out_dir = dir.join("out");
+    fs::create_dir(&out_dir).unwrap();
+    fs::write(out_dir.join("delete_blob_builder.rs"), "pub struct Mine;\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_bpb"))
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .arg(&definitions)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("delete_blob_builder.rs: not generated by bpb"));
+    assert_eq!(
+        fs::read_to_string(out_dir.join("delete_blob_builder.rs")).unwrap(),
+        "pub struct Mine;\n"
+    );
+    assert_files(&out_dir, &["delete_blob_builder.rs"]);
+}
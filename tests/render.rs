@@ -0,0 +1,49 @@
+//! Renders several builders into one file, hoisting the `use` items they
+//! start with above them.
+
+extern crate bpb;
+extern crate syn;
+
+#[test]
+fn only_leading_use_items_are_hoisted() {
+    let structs = bpb::from_str_all(
+        r#"[
+            {
+                "name": "FooBuilder",
+                "fields": [],
+                "finalizers": [
+                    { "name": "finalize", "return_type": "&'static str", "body": "let s = \"we use this\";\ns" }
+                ]
+            },
+            { "name": "BarBuilder", "uses": ["zzz::Z"], "fields": [] }
+        ]"#,
+    )
+    .unwrap();
+    let outputs: Vec<String> = structs
+        .iter()
+        .map(|stc| bpb::generate(stc).unwrap())
+        .collect();
+    let output = bpb::render(&outputs);
+
+    assert_eq!(output.matches("use zzz::Z;").count(), 1);
+    assert!(output.find("use zzz::Z;") < output.find("pub struct FooBuilder"));
+    assert!(output.contains(
+        "    pub fn finalize(self) -> &'static str {\n        let s = \"we use this\";\n        s\n    }\n"
+    ));
+    assert!(!output.contains("\n\n\n"));
+    syn::parse_file(&output).unwrap();
+}
+
+#[test]
+fn public_use_items_are_hoisted() {
+    let output = bpb::render(&[
+        "pub use a::A;\npub(crate) use b::B;\n\npub struct FooBuilder;\n",
+        "use c::C;\n\npub struct BarBuilder;\n",
+    ]);
+    assert_eq!(
+        output,
+        "// This file is @generated by bpb, do not edit it by hand.\n\n\
+         pub use a::A;\npub(crate) use b::B;\nuse c::C;\n\n\
+         pub struct FooBuilder;\n\npub struct BarBuilder;\n"
+    );
+}
@@ -0,0 +1,256 @@
+//! Generates builders from definitions mixing many features, with long
+//! names, long types, long default values and finalizer bodies written the
+//! way rustfmt formats them, and checks that rustfmt leaves the output as it
+//! is.
+
+extern crate bpb;
+
+use std::io::prelude::*;
+use std::io::ErrorKind;
+use std::process::{Command, Stdio};
+
+// `output` formatted by rustfmt, `None` if rustfmt is not installed
+fn rustfmt(output: &str) -> Option<String> {
+    let mut child = match Command::new("rustfmt")
+        .args(["--edition", "2018", "--emit", "stdout"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(ref e) if e.kind() == ErrorKind::NotFound => {
+            eprintln!("rustfmt is not installed, the output is not checked");
+            return None;
+        }
+        Err(e) => panic!("cannot run rustfmt: {}", e),
+    };
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(output.as_bytes())
+        .unwrap();
+    let result = child.wait_with_output().unwrap();
+    assert!(
+        result.status.success(),
+        "rustfmt cannot format the output\n{}\n{}",
+        output,
+        String::from_utf8_lossy(&result.stderr)
+    );
+    Some(String::from_utf8(result.stdout).unwrap())
+}
+
+fn check(definitions: &str) {
+    let outputs: Vec<String> = bpb::from_str_all(definitions)
+        .unwrap()
+        .iter()
+        .map(|stc| bpb::generate(stc).unwrap())
+        .collect();
+    let output = bpb::render(&outputs);
+
+    if let Some(formatted) = rustfmt(&output) {
+        assert!(
+            formatted == output,
+            "rustfmt changes the output\n--- generated\n{}\n--- rustfmt\n{}",
+            output,
+            formatted
+        );
+    }
+}
+
+#[test]
+fn use_lists_are_sorted() {
+    check(
+        r#"{
+            "name": "ListQueuesBuilder",
+            "uses": [
+                "typestate::{ToAssign, Yes, No}",
+                "std::{fmt::{Display, Debug}, collections::HashMap, borrow::Cow}",
+                "crate::{Client, self}"
+            ],
+            "fields": [
+                { "name": "prefix", "field_type": "Cow<'static, str>", "builder_type": "PrefixSet" }
+            ]
+        }"#,
+    );
+}
+
+#[test]
+fn finalizer_bodies_are_kept_as_written() {
+    check(
+        r#"{
+            "name": "SetContainerMetadataBuilder",
+            "extra_types": ["'a"],
+            "uses": ["std::collections::HashMap"],
+            "fields": [
+                { "name": "container_name", "field_type": "&'a str", "builder_type": "ContainerNameSet" },
+                { "name": "timeout", "field_type": "u64", "optional": true }
+            ],
+            "finalizers": [
+                {
+                    "name": "finalize",
+                    "return_type": "HashMap<String, String>",
+                    "by_ref": true,
+                    "body": "let mut map = HashMap::new();\nmap.insert(\n    \"container_name_with_a_long_key\".to_owned(),\n    self.container_name().to_owned() + \"suffix\",\n);\n\nprintln!(\n    \"{} {} {}\",\n    self.container_name(),\n    self.timeout.unwrap_or_default(),\n    \"a fairly long literal string here\"\n);\nmap"
+                },
+                {
+                    "name": "describe",
+                    "return_type": "String",
+                    "by_ref": true,
+                    "body": "    // indented as a whole\n    if let Some(timeout) = self.timeout {\n        format!(\"{} after {}s\", self.container_name(), timeout)\n    } else {\n        self.container_name().to_owned()\n    }\n"
+                },
+                { "name": "send", "async": true, "visibility": "pub(crate)" }
+            ]
+        }"#,
+    );
+}
+
+#[test]
+fn long_generics_and_types_are_wrapped() {
+    check(
+        r#"{
+            "name": "PutBlockFromUrlWithVeryLongNameBuilder",
+            "derive": "Debug, Clone",
+            "inline": true,
+            "emit_traits": true,
+            "extra_types": [
+                "'a",
+                "'b: 'a",
+                { "type": "SourceUrl", "bounds": ["AsRef<str>", "Clone", "'a"] },
+                "const BLOCK_SIZE_IN_BYTES: usize"
+            ],
+            "extra_wheres": ["SourceUrl: std::fmt::Debug + Send + Sync + 'static"],
+            "constructor_fields": [
+                { "name": "client", "field_type": "&'a Client", "trait_get": "ClientRequired<'a>" }
+            ],
+            "fields": [
+                {
+                    "name": "source_url",
+                    "field_type": "SourceUrl",
+                    "builder_type": "SourceUrlSet",
+                    "trait_get": "SourceUrlRequired<SourceUrl>",
+                    "trait_set": "SourceUrlSupport<SourceUrl>"
+                },
+                {
+                    "name": "block_identifier_with_a_long_name",
+                    "field_type": "std::collections::HashMap<&'b str, Vec<(u64, Option<String>)>>",
+                    "builder_type": "BlockIdentifierWithALongNameSet",
+                    "collection": true
+                },
+                {
+                    "name": "content_md5",
+                    "field_type": "&'b [u8]",
+                    "optional": true,
+                    "trait_get": "ContentMD5Option<'b>",
+                    "trait_set": "ContentMD5Support<'b>"
+                },
+                { "name": "lease_ids", "field_type": "Vec<&'a str>", "optional": true, "collection": true },
+                { "name": "retries", "field_type": "u32", "optional": true, "default": "3", "setter_into": true },
+                { "name": "tag", "field_type": "&'a str", "optional": true, "setter_as_ref": true },
+                { "name": "label", "field_type": "String", "optional": true, "setter_impl_trait": true }
+            ],
+            "finalizers": [
+                {
+                    "name": "finalize",
+                    "return_type": "Result<(SourceUrl, [u8; BLOCK_SIZE_IN_BYTES]), Box<dyn std::error::Error + Send + Sync>>",
+                    "body": "Ok((self.source_url, [0; BLOCK_SIZE_IN_BYTES]))"
+                }
+            ]
+        }"#,
+    );
+}
+
+#[test]
+fn several_builders_share_a_file() {
+    check(
+        r#"[
+            {
+                "name": "GetMessagesBuilder",
+                "visibility": "pub(crate)",
+                "prelude_path": "crate::typestate",
+                "uses": ["std::time::Duration"],
+                "fields": [
+                    { "name": "queue_name", "field_type": "String", "builder_type": "QueueNameSet", "get_via_clone": true },
+                    { "name": "visibility_timeout", "field_type": "Duration", "optional": true }
+                ]
+            },
+            {
+                "name": "PeekMessagesBuilder",
+                "prelude_path": "crate::typestate",
+                "typestate": { "set": "Assigned", "unset": "Unassigned" },
+                "uses": ["std::time::Duration", "std::borrow::Cow"],
+                "fields": [
+                    { "name": "queue_name", "field_type": "Cow<'static, str>", "builder_type": "QueueNameSet" },
+                    { "name": "number_of_messages", "field_type": "u8", "optional": true, "default_trait": true }
+                ]
+            }
+        ]"#,
+    );
+}
+
+#[test]
+fn long_setters_and_defaults_are_wrapped() {
+    check(
+        r#"{
+            "name": "SetBlobPropertiesWithLongNamesBuilder",
+            "extra_types": ["'a"],
+            "fields": [
+                {
+                    "name": "some_long_name",
+                    "field_type": "&'a std::collections::HashMap<String, Vec<u8>>",
+                    "builder_type": "SomeLongNameSet",
+                    "setter_into": true
+                },
+                {
+                    "name": "content_disposition_of_the_blob",
+                    "field_type": "Option<String>",
+                    "optional": true,
+                    "default": "Some(String::from(\"attachment; filename=\\\"a_very_long_file_name.txt\\\"\"))"
+                },
+                {
+                    "name": "allowed_content_types_of_the_blob",
+                    "field_type": "Vec<String>",
+                    "optional": true,
+                    "default": "vec![String::from(\"application/json\"), String::from(\"application/octet-stream\"), String::from(\"text/plain\")]"
+                },
+                {
+                    "name": "metadata",
+                    "field_type": "Vec<(String, String)>",
+                    "optional": true,
+                    "default": "vec![(String::from(\"first_metadata_key\"), String::from(\"first_metadata_value\"))]"
+                },
+                {
+                    "name": "a_field_whose_name_is_long_enough",
+                    "field_type": "&'a std::collections::HashMap<String, Vec<std::borrow::Cow<'a, str>>>",
+                    "builder_type": "AFieldWhoseNameIsLongEnoughSet",
+                    "setter_into": true
+                },
+                {
+                    "name": "blob_tag_list",
+                    "field_type": "&'a std::collections::HashMap<String, Vec<std::borrow::Cow<'a, str>>>",
+                    "builder_type": "BlobTagListSet",
+                    "setter_into": true
+                },
+                {
+                    "name": "range",
+                    "field_type": "Option<(u64, u64)>",
+                    "optional": true,
+                    "default": "Some(some_module::compute_the_default_range_of_the_blob(first_argument, second_argument))"
+                },
+                {
+                    "name": "block_sizes",
+                    "field_type": "Vec<u64>",
+                    "optional": true,
+                    "default": "vec![1024, 2048, 4096, 8192, 16384, 32768, 65536, 131072, 262144, 524288, 1048576]"
+                },
+                {
+                    "name": "owner",
+                    "field_type": "Option<String>",
+                    "optional": true,
+                    "default": "Some(a_variable_with_a_very_long_name_to_push_the_argument_past_the_limit)"
+                }
+            ]
+        }"#,
+    );
+}
@@ -0,0 +1,118 @@
+//! Checks the JSON Schema printed by `bpb schema` against the example
+//! definitions at the root of the repository and the fixtures of the
+//! collection and default features.
+
+extern crate bpb;
+extern crate jsonschema;
+extern crate serde_json;
+
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+
+fn schema() -> Value {
+    serde_json::from_str(&bpb::schema()).unwrap()
+}
+
+// `fixture` is relative to the root of the repository
+fn definition(fixture: &str) -> Value {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let text = fs::read_to_string(root.join(format!("{}.json", fixture))).unwrap();
+    serde_json::from_str(&text).unwrap()
+}
+
+#[test]
+fn examples_are_valid() {
+    let validator = jsonschema::validator_for(&schema()).unwrap();
+    for fixture in &[
+        "simple",
+        "test",
+        "put_block_list_builder",
+        "change_blob_lease_builder",
+        "tests/fixtures/set_blob_metadata_builder",
+        "tests/fixtures/list_blobs_builder",
+    ] {
+        let errors = validator
+            .iter_errors(&definition(fixture))
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>();
+        assert!(errors.is_empty(), "{}.json: {:?}", fixture, errors);
+    }
+}
+
+#[test]
+fn unknown_keys_and_wrong_types_are_invalid() {
+    let validator = jsonschema::validator_for(&schema()).unwrap();
+
+    let mut typo = definition("put_block_list_builder");
+    typo["fields"][0]["optinal"] = Value::Bool(true);
+    assert!(!validator.is_valid(&typo));
+
+    let mut wrong_type = definition("put_block_list_builder");
+    wrong_type["inline"] = Value::String("yes".to_owned());
+    assert!(!validator.is_valid(&wrong_type));
+
+    let mut missing = definition("put_block_list_builder");
+    missing.as_object_mut().unwrap().remove("fields");
+    assert!(!validator.is_valid(&missing));
+}
+
+#[test]
+fn fields_need_a_name_and_type_unless_taken_from_the_catalog() {
+    let validator = jsonschema::validator_for(&schema()).unwrap();
+
+    let mut from_catalog = definition("put_block_list_builder");
+    from_catalog["fields"][0] = serde_json::json!({ "use": "timeout" });
+    assert!(validator.is_valid(&from_catalog));
+
+    let mut no_type = definition("put_block_list_builder");
+    no_type["fields"][0] = serde_json::json!({ "name": "timeout", "optional": true });
+    assert!(!validator.is_valid(&no_type));
+}
+
+#[test]
+fn visibilities_are_checked() {
+    let validator = jsonschema::validator_for(&schema()).unwrap();
+    for &(visibility, valid) in &[
+        ("pub", true),
+        ("pub(crate)", true),
+        ("pub(super)", true),
+        ("pub(in crate::blob)", true),
+        ("private", true),
+        ("public", false),
+        ("pub(everywhere)", false),
+        ("", false),
+    ] {
+        let mut struct_visibility = definition("simple");
+        struct_visibility["visibility"] = Value::from(visibility);
+        assert_eq!(
+            validator.is_valid(&struct_visibility),
+            valid,
+            "{}",
+            visibility
+        );
+
+        let mut field_visibility = definition("put_block_list_builder");
+        field_visibility["fields"][0]["visibility"] = Value::from(visibility);
+        assert_eq!(
+            validator.is_valid(&field_visibility),
+            valid,
+            "{}",
+            visibility
+        );
+    }
+}
+
+#[test]
+fn flags_are_described() {
+    let schema = schema();
+    for pointer in &[
+        "/properties/inline",
+        "/properties/prepend_required_types",
+        "/$defs/Field/properties/get_via_clone",
+        "/$defs/ConstructorField/properties/get_via_clone",
+    ] {
+        let description = schema.pointer(&format!("{}/description", pointer));
+        assert!(description.is_some(), "{} has no description", pointer);
+    }
+}
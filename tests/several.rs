@@ -0,0 +1,88 @@
+//! Loads several definitions at once, from an array or from JSON Lines,
+//! and reports the problems of each one under its index.
+
+extern crate bpb;
+
+mod common;
+
+use common::{dir, messages};
+use std::fs;
+
+fn names(structs: &[bpb::Struct]) -> Vec<&str> {
+    structs.iter().map(|stc| stc.name.as_str()).collect()
+}
+
+const ARRAY: &str = r#"[
+    { "name": "GetBlobBuilder", "fields": [] },
+    { "name": "PutBlobBuilder", "fields": [] }
+]"#;
+
+const LINES: &str = r#"{ "name": "GetBlobBuilder", "fields": [] }
+
+{ "name": "PutBlobBuilder", "fields": [] }
+"#;
+
+#[test]
+fn arrays_hold_several_definitions() {
+    let structs = bpb::from_str_all(ARRAY).unwrap();
+    assert_eq!(names(&structs), vec!["GetBlobBuilder", "PutBlobBuilder"]);
+
+    let structs = bpb::from_str_all(r#"{ "name": "GetBlobBuilder", "fields": [] }"#).unwrap();
+    assert_eq!(names(&structs), vec!["GetBlobBuilder"]);
+}
+
+#[test]
+fn problems_in_arrays_are_reported_under_their_index() {
+    let text = r#"[
+    { "name": "GetBlobBuilder", "fields": [] },
+    { "name": "Put Blob Builder", "fields": [] }
+]"#;
+    let messages = messages(bpb::from_str_all(text).unwrap_err());
+    assert_eq!(messages.len(), 1, "{:?}", messages);
+    assert!(
+        messages[0].starts_with("3:7: [1].name: `Put Blob Builder` is not an identifier"),
+        "{}",
+        messages[0]
+    );
+}
+
+#[test]
+fn json_lines_hold_one_definition_per_line() {
+    let structs = bpb::from_str_lines(LINES).unwrap();
+    assert_eq!(names(&structs), vec!["GetBlobBuilder", "PutBlobBuilder"]);
+}
+
+#[test]
+fn problems_in_json_lines_are_reported_under_their_index_and_line() {
+    // the blank line counts for the location but not for the index
+    let text = r#"{ "name": "GetBlobBuilder", "fields": [] }
+
+{ "name": "Put Blob Builder", "fields": [] }
+{ "name": "ListBlobsBuilder", "fields": [ }
+"#;
+    let messages = messages(bpb::from_str_lines(text).unwrap_err());
+    assert_eq!(messages.len(), 2, "{:?}", messages);
+    assert!(
+        messages[0].starts_with("3:3: [1].name: `Put Blob Builder` is not an identifier"),
+        "{}",
+        messages[0]
+    );
+    assert_eq!(messages[1], "4:43: [2]: expected value");
+}
+
+#[test]
+fn files_are_read_by_extension() {
+    let dir = dir("files_are_read_by_extension");
+    fs::write(dir.join("builders.json"), ARRAY).unwrap();
+    fs::write(dir.join("builders.jsonl"), LINES).unwrap();
+
+    for file in ["builders.json", "builders.jsonl"].iter() {
+        let structs = bpb::load_all(dir.join(file)).unwrap();
+        assert_eq!(
+            names(&structs),
+            vec!["GetBlobBuilder", "PutBlobBuilder"],
+            "{}",
+            file
+        );
+    }
+}
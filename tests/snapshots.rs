@@ -0,0 +1,143 @@
+//! Runs the generator on the example definitions at the root of the
+//! repository and on the fixtures in `tests/fixtures`, and compares the
+//! output with the snapshot stored in `tests/snapshots`. The fixtures are
+//! the examples `simple.json` and `test.json`, completed so that their
+//! output compiles, `put_block_list_builder.json` given a finalizer without
+//! a body, and definitions exercising a single feature; a fixture holding
+//! several definitions is rendered as one file. The typestate preludes are
+//! compared the same way.
+//!
+//! After an intended change to the generated code, rerun with
+//! `BPB_UPDATE_SNAPSHOTS=1` to rewrite the snapshots and review the diff.
+//! The snapshots are formatted in-process, so they do not depend on the
+//! tools installed on the machine; where `rustfmt` is installed, they are
+//! also checked to be formatted exactly as it formats them.
+
+extern crate bpb;
+
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::process::Command;
+
+// `dir` is relative to the root of the repository
+fn check_snapshot(dir: &str, fixture: &str) {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let definitions = bpb::load_all(root.join(dir).join(format!("{}.json", fixture))).unwrap();
+    let outputs: Vec<String> = definitions
+        .iter()
+        .map(|stc| bpb::generate(stc).unwrap())
+        .collect();
+    let output = bpb::render(&outputs);
+
+    compare_snapshot(&format!("{}.json", fixture), fixture, &output);
+}
+
+// the prelude generated for `typestate`
+fn check_prelude_snapshot(typestate: &bpb::Typestate, name: &str) {
+    let output = bpb::render(&[bpb::generate_prelude(typestate).unwrap()]);
+    compare_snapshot("the prelude", name, &output);
+}
+
+fn compare_snapshot(what: &str, name: &str, output: &str) {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let snapshot = root.join("tests/snapshots").join(format!("{}.rs", name));
+    if env::var_os("BPB_UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&snapshot, output).unwrap();
+        return;
+    }
+
+    if let Some(diff) = bpb::check_file(&snapshot, output).unwrap() {
+        panic!(
+            "the output for {} differs from its snapshot, rerun with BPB_UPDATE_SNAPSHOTS=1 if the change is intended\n{}",
+            what, diff
+        );
+    }
+}
+
+#[test]
+fn simple() {
+    check_snapshot("tests/fixtures", "simple");
+}
+
+#[test]
+fn test() {
+    check_snapshot("tests/fixtures", "test");
+}
+
+#[test]
+fn change_blob_lease_builder() {
+    check_snapshot(".", "change_blob_lease_builder");
+}
+
+#[test]
+fn put_block_list_builder() {
+    check_snapshot("tests/fixtures", "put_block_list_builder");
+}
+
+#[test]
+fn set_blob_metadata_builder() {
+    check_snapshot("tests/fixtures", "set_blob_metadata_builder");
+}
+
+#[test]
+fn list_blobs_builder() {
+    check_snapshot("tests/fixtures", "list_blobs_builder");
+}
+
+#[test]
+fn visibility() {
+    check_snapshot("tests/fixtures", "visibility");
+}
+
+#[test]
+fn setters() {
+    check_snapshot("tests/fixtures", "setters");
+}
+
+#[test]
+fn shared_prelude() {
+    check_snapshot("tests/fixtures", "shared_prelude");
+}
+
+#[test]
+fn prelude() {
+    check_prelude_snapshot(&bpb::Typestate::default(), "prelude");
+}
+
+#[test]
+fn prelude_with_renamed_enum_markers() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let typestate = bpb::load_typestate(root.join("tests/fixtures/typestate.toml")).unwrap();
+    check_prelude_snapshot(&typestate, "typestate");
+}
+
+#[test]
+fn snapshots_are_formatted_like_rustfmt() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let mut snapshots: Vec<PathBuf> = fs::read_dir(root.join("tests/snapshots"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    snapshots.sort();
+
+    let output = match Command::new("rustfmt")
+        .args(["--edition", "2018", "--check"])
+        .args(&snapshots)
+        .output()
+    {
+        Ok(output) => output,
+        Err(ref e) if e.kind() == ErrorKind::NotFound => {
+            eprintln!("rustfmt is not installed, the snapshots are not checked");
+            return;
+        }
+        Err(e) => panic!("cannot run rustfmt: {}", e),
+    };
+    assert!(
+        output.status.success(),
+        "the snapshots are not formatted like rustfmt formats them\n{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
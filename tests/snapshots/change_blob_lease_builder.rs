@@ -0,0 +1,121 @@
+// This file is @generated by bpb, do not edit it by hand.
+
+pub struct ChangeBlobLeaseBuilder<'a, ContainerNameSet>
+where
+    ContainerNameSet: ToAssign,
+{
+    client: &'a Client,
+    p_container_name: PhantomData<ContainerNameSet>,
+    container_name: Option<&'a str>,
+    timeout: Option<u64>,
+    client_request_id: Option<&'a str>,
+}
+
+impl<'a> ChangeBlobLeaseBuilder<'a, No> {
+    #[inline]
+    pub(crate) fn new(client: &'a Client) -> ChangeBlobLeaseBuilder<'a, No> {
+        ChangeBlobLeaseBuilder {
+            client,
+            p_container_name: PhantomData {},
+            container_name: None,
+            timeout: None,
+            client_request_id: None,
+        }
+    }
+}
+
+impl<'a, ContainerNameSet> ClientRequired<'a> for ChangeBlobLeaseBuilder<'a, ContainerNameSet>
+where
+    ContainerNameSet: ToAssign,
+{
+    #[inline]
+    fn client(&self) -> &'a Client {
+        self.client
+    }
+}
+
+impl<'a> ContainerNameRequired<'a> for ChangeBlobLeaseBuilder<'a, Yes> {
+    #[inline]
+    fn container_name(&self) -> &'a str {
+        self.container_name.unwrap()
+    }
+}
+
+impl<'a, ContainerNameSet> TimeoutOption for ChangeBlobLeaseBuilder<'a, ContainerNameSet>
+where
+    ContainerNameSet: ToAssign,
+{
+    #[inline]
+    fn timeout(&self) -> Option<u64> {
+        self.timeout
+    }
+}
+
+impl<'a, ContainerNameSet> ClientRequestIdOption<'a>
+    for ChangeBlobLeaseBuilder<'a, ContainerNameSet>
+where
+    ContainerNameSet: ToAssign,
+{
+    #[inline]
+    fn client_request_id(&self) -> Option<&'a str> {
+        self.client_request_id
+    }
+}
+
+impl<'a> ContainerNameSupport<'a> for ChangeBlobLeaseBuilder<'a, No> {
+    type O = ChangeBlobLeaseBuilder<'a, Yes>;
+    #[inline]
+    fn with_container_name(self, container_name: &'a str) -> Self::O {
+        ChangeBlobLeaseBuilder {
+            client: self.client,
+            p_container_name: PhantomData {},
+            container_name: Some(container_name),
+            timeout: self.timeout,
+            client_request_id: self.client_request_id,
+        }
+    }
+}
+
+impl<'a, ContainerNameSet> TimeoutSupport for ChangeBlobLeaseBuilder<'a, ContainerNameSet>
+where
+    ContainerNameSet: ToAssign,
+{
+    type O = ChangeBlobLeaseBuilder<'a, ContainerNameSet>;
+    #[inline]
+    fn with_timeout(self, timeout: u64) -> Self::O {
+        ChangeBlobLeaseBuilder {
+            client: self.client,
+            p_container_name: PhantomData {},
+            container_name: self.container_name,
+            timeout: Some(timeout),
+            client_request_id: self.client_request_id,
+        }
+    }
+}
+
+impl<'a, ContainerNameSet> ClientRequestIdSupport<'a>
+    for ChangeBlobLeaseBuilder<'a, ContainerNameSet>
+where
+    ContainerNameSet: ToAssign,
+{
+    type O = ChangeBlobLeaseBuilder<'a, ContainerNameSet>;
+    #[inline]
+    fn with_client_request_id(self, client_request_id: &'a str) -> Self::O {
+        ChangeBlobLeaseBuilder {
+            client: self.client,
+            p_container_name: PhantomData {},
+            container_name: self.container_name,
+            timeout: self.timeout,
+            client_request_id: Some(client_request_id),
+        }
+    }
+}
+
+// methods callable regardless
+impl<'a, ContainerNameSet> ChangeBlobLeaseBuilder<'a, ContainerNameSet> where
+    ContainerNameSet: ToAssign
+{
+}
+
+// methods callable only when every mandatory field has been filled
+impl<'a> ChangeBlobLeaseBuilder<'a, Yes> {}
@@ -0,0 +1,325 @@
+// This file is @generated by bpb, do not edit it by hand.
+
+// getter and setter traits
+pub trait ClientRequired<'a> {
+    fn client(&self) -> &'a Client;
+}
+
+pub trait PrefixRequired {
+    fn prefix(&self) -> String;
+}
+
+pub trait PrefixSupport {
+    type O;
+    fn with_prefix(self, prefix: String) -> Self::O;
+}
+
+pub trait DelimiterOption<'a> {
+    fn delimiter(&self) -> Option<&'a str>;
+}
+
+pub trait DelimiterSupport<'a> {
+    type O;
+    fn with_delimiter(self, delimiter: &'a str) -> Self::O;
+}
+
+pub trait TimeoutDefault {
+    fn timeout(&self) -> u64;
+}
+
+pub trait TimeoutDefaultSupport {
+    type O;
+    fn with_timeout(self, timeout: u64) -> Self::O;
+}
+
+pub struct ListBlobsBuilder<'a, ContainerNameSet, MaxResultsSet, PrefixSet>
+where
+    ContainerNameSet: ToAssign,
+    MaxResultsSet: ToAssign,
+    PrefixSet: ToAssign,
+{
+    client: &'a Client,
+    p_container_name: PhantomData<ContainerNameSet>,
+    p_max_results: PhantomData<MaxResultsSet>,
+    p_prefix: PhantomData<PrefixSet>,
+    container_name: Option<&'a str>,
+    max_results: u32,
+    prefix: String,
+    delimiter: Option<&'a str>,
+    timeout: u64,
+    include_snapshots: bool,
+    marker: Option<&'a str>,
+}
+
+impl<'a> ListBlobsBuilder<'a, No, No, No> {
+    pub(crate) fn new(client: &'a Client) -> ListBlobsBuilder<'a, No, No, No> {
+        ListBlobsBuilder {
+            client,
+            p_container_name: PhantomData {},
+            container_name: None,
+            p_max_results: PhantomData {},
+            max_results: 5000,
+            p_prefix: PhantomData {},
+            prefix: Default::default(),
+            delimiter: None,
+            timeout: 30,
+            include_snapshots: Default::default(),
+            marker: None,
+        }
+    }
+}
+
+impl<'a, ContainerNameSet, MaxResultsSet, PrefixSet> ClientRequired<'a>
+    for ListBlobsBuilder<'a, ContainerNameSet, MaxResultsSet, PrefixSet>
+where
+    ContainerNameSet: ToAssign,
+    MaxResultsSet: ToAssign,
+    PrefixSet: ToAssign,
+{
+    fn client(&self) -> &'a Client {
+        self.client
+    }
+}
+
+// get mandatory no traits methods
+impl<'a, MaxResultsSet, PrefixSet> ListBlobsBuilder<'a, Yes, MaxResultsSet, PrefixSet>
+where
+    MaxResultsSet: ToAssign,
+    PrefixSet: ToAssign,
+{
+    pub fn container_name(&self) -> &'a str {
+        self.container_name.unwrap()
+    }
+}
+
+impl<'a, ContainerNameSet, PrefixSet> ListBlobsBuilder<'a, ContainerNameSet, Yes, PrefixSet>
+where
+    ContainerNameSet: ToAssign,
+    PrefixSet: ToAssign,
+{
+    pub fn max_results(&self) -> u32 {
+        self.max_results
+    }
+}
+
+// set mandatory no traits methods
+impl<'a, MaxResultsSet, PrefixSet> ListBlobsBuilder<'a, No, MaxResultsSet, PrefixSet>
+where
+    MaxResultsSet: ToAssign,
+    PrefixSet: ToAssign,
+{
+    pub fn with_container_name(
+        self,
+        container_name: &'a str,
+    ) -> ListBlobsBuilder<'a, Yes, MaxResultsSet, PrefixSet> {
+        ListBlobsBuilder {
+            client: self.client,
+            p_container_name: PhantomData {},
+            p_max_results: PhantomData {},
+            p_prefix: PhantomData {},
+            container_name: Some(container_name),
+            max_results: self.max_results,
+            prefix: self.prefix,
+            delimiter: self.delimiter,
+            timeout: self.timeout,
+            include_snapshots: self.include_snapshots,
+            marker: self.marker,
+        }
+    }
+}
+
+impl<'a, ContainerNameSet, PrefixSet> ListBlobsBuilder<'a, ContainerNameSet, No, PrefixSet>
+where
+    ContainerNameSet: ToAssign,
+    PrefixSet: ToAssign,
+{
+    pub fn with_max_results(
+        self,
+        max_results: u32,
+    ) -> ListBlobsBuilder<'a, ContainerNameSet, Yes, PrefixSet> {
+        ListBlobsBuilder {
+            client: self.client,
+            p_container_name: PhantomData {},
+            p_max_results: PhantomData {},
+            p_prefix: PhantomData {},
+            container_name: self.container_name,
+            max_results,
+            prefix: self.prefix,
+            delimiter: self.delimiter,
+            timeout: self.timeout,
+            include_snapshots: self.include_snapshots,
+            marker: self.marker,
+        }
+    }
+}
+
+impl<'a, ContainerNameSet, MaxResultsSet> PrefixRequired
+    for ListBlobsBuilder<'a, ContainerNameSet, MaxResultsSet, Yes>
+where
+    ContainerNameSet: ToAssign,
+    MaxResultsSet: ToAssign,
+{
+    fn prefix(&self) -> String {
+        self.prefix.clone()
+    }
+}
+
+impl<'a, ContainerNameSet, MaxResultsSet, PrefixSet> DelimiterOption<'a>
+    for ListBlobsBuilder<'a, ContainerNameSet, MaxResultsSet, PrefixSet>
+where
+    ContainerNameSet: ToAssign,
+    MaxResultsSet: ToAssign,
+    PrefixSet: ToAssign,
+{
+    fn delimiter(&self) -> Option<&'a str> {
+        self.delimiter
+    }
+}
+
+impl<'a, ContainerNameSet, MaxResultsSet, PrefixSet> TimeoutDefault
+    for ListBlobsBuilder<'a, ContainerNameSet, MaxResultsSet, PrefixSet>
+where
+    ContainerNameSet: ToAssign,
+    MaxResultsSet: ToAssign,
+    PrefixSet: ToAssign,
+{
+    fn timeout(&self) -> u64 {
+        self.timeout
+    }
+}
+
+impl<'a, ContainerNameSet, MaxResultsSet> PrefixSupport
+    for ListBlobsBuilder<'a, ContainerNameSet, MaxResultsSet, No>
+where
+    ContainerNameSet: ToAssign,
+    MaxResultsSet: ToAssign,
+{
+    type O = ListBlobsBuilder<'a, ContainerNameSet, MaxResultsSet, Yes>;
+    fn with_prefix(self, prefix: String) -> Self::O {
+        ListBlobsBuilder {
+            client: self.client,
+            p_container_name: PhantomData {},
+            p_max_results: PhantomData {},
+            p_prefix: PhantomData {},
+            container_name: self.container_name,
+            max_results: self.max_results,
+            prefix,
+            delimiter: self.delimiter,
+            timeout: self.timeout,
+            include_snapshots: self.include_snapshots,
+            marker: self.marker,
+        }
+    }
+}
+
+impl<'a, ContainerNameSet, MaxResultsSet, PrefixSet> DelimiterSupport<'a>
+    for ListBlobsBuilder<'a, ContainerNameSet, MaxResultsSet, PrefixSet>
+where
+    ContainerNameSet: ToAssign,
+    MaxResultsSet: ToAssign,
+    PrefixSet: ToAssign,
+{
+    type O = ListBlobsBuilder<'a, ContainerNameSet, MaxResultsSet, PrefixSet>;
+    fn with_delimiter(self, delimiter: &'a str) -> Self::O {
+        ListBlobsBuilder {
+            client: self.client,
+            p_container_name: PhantomData {},
+            p_max_results: PhantomData {},
+            p_prefix: PhantomData {},
+            container_name: self.container_name,
+            max_results: self.max_results,
+            prefix: self.prefix,
+            delimiter: Some(delimiter),
+            timeout: self.timeout,
+            include_snapshots: self.include_snapshots,
+            marker: self.marker,
+        }
+    }
+}
+
+impl<'a, ContainerNameSet, MaxResultsSet, PrefixSet> TimeoutDefaultSupport
+    for ListBlobsBuilder<'a, ContainerNameSet, MaxResultsSet, PrefixSet>
+where
+    ContainerNameSet: ToAssign,
+    MaxResultsSet: ToAssign,
+    PrefixSet: ToAssign,
+{
+    type O = ListBlobsBuilder<'a, ContainerNameSet, MaxResultsSet, PrefixSet>;
+    fn with_timeout(self, timeout: u64) -> Self::O {
+        ListBlobsBuilder {
+            client: self.client,
+            p_container_name: PhantomData {},
+            p_max_results: PhantomData {},
+            p_prefix: PhantomData {},
+            container_name: self.container_name,
+            max_results: self.max_results,
+            prefix: self.prefix,
+            delimiter: self.delimiter,
+            timeout,
+            include_snapshots: self.include_snapshots,
+            marker: self.marker,
+        }
+    }
+}
+
+// methods callable regardless
+impl<'a, ContainerNameSet, MaxResultsSet, PrefixSet>
+    ListBlobsBuilder<'a, ContainerNameSet, MaxResultsSet, PrefixSet>
+where
+    ContainerNameSet: ToAssign,
+    MaxResultsSet: ToAssign,
+    PrefixSet: ToAssign,
+{
+    pub fn include_snapshots(&self) -> bool {
+        self.include_snapshots
+    }
+    pub fn marker(&self) -> Option<&'a str> {
+        self.marker
+    }
+    pub fn with_include_snapshots(self, include_snapshots: bool) -> Self {
+        ListBlobsBuilder {
+            client: self.client,
+            p_container_name: self.p_container_name,
+            p_max_results: self.p_max_results,
+            p_prefix: self.p_prefix,
+            container_name: self.container_name,
+            max_results: self.max_results,
+            prefix: self.prefix,
+            delimiter: self.delimiter,
+            timeout: self.timeout,
+            include_snapshots,
+            marker: self.marker,
+        }
+    }
+    pub fn with_marker(self, marker: &'a str) -> Self {
+        ListBlobsBuilder {
+            client: self.client,
+            p_container_name: self.p_container_name,
+            p_max_results: self.p_max_results,
+            p_prefix: self.p_prefix,
+            container_name: self.container_name,
+            max_results: self.max_results,
+            prefix: self.prefix,
+            delimiter: self.delimiter,
+            timeout: self.timeout,
+            include_snapshots: self.include_snapshots,
+            marker: Some(marker),
+        }
+    }
+}
+
+// methods callable only when every mandatory field has been filled
+impl<'a> ListBlobsBuilder<'a, Yes, Yes, Yes> {
+    pub fn query(&self) -> String {
+        format!(
+            "{}?prefix={}&maxresults={}&timeout={}&snapshots={}&delimiter={:?}&marker={:?}",
+            self.container_name(),
+            self.prefix,
+            self.max_results,
+            self.timeout,
+            self.include_snapshots,
+            self.delimiter,
+            self.marker
+        )
+    }
+}
@@ -0,0 +1,203 @@
+// This file is @generated by bpb, do not edit it by hand.
+
+pub struct PutBlockListBuilder<'a, T, ContainerNameSet, BlockListSet>
+where
+    ContainerNameSet: ToAssign,
+    BlockListSet: ToAssign,
+    T: Borrow<[u8]> + 'a,
+{
+    client: &'a Client,
+    p_container_name: PhantomData<ContainerNameSet>,
+    p_block_list: PhantomData<BlockListSet>,
+    container_name: Option<&'a str>,
+    block_list: Option<&'a BlockList<T>>,
+    timeout: Option<u64>,
+    client_request_id: Option<&'a str>,
+}
+
+impl<'a, T> PutBlockListBuilder<'a, T, No, No>
+where
+    T: Borrow<[u8]> + 'a,
+{
+    #[inline]
+    pub(crate) fn new(client: &'a Client) -> PutBlockListBuilder<'a, T, No, No> {
+        PutBlockListBuilder {
+            client,
+            p_container_name: PhantomData {},
+            container_name: None,
+            p_block_list: PhantomData {},
+            block_list: None,
+            timeout: None,
+            client_request_id: None,
+        }
+    }
+}
+
+impl<'a, T, ContainerNameSet, BlockListSet> ClientRequired<'a>
+    for PutBlockListBuilder<'a, T, ContainerNameSet, BlockListSet>
+where
+    ContainerNameSet: ToAssign,
+    BlockListSet: ToAssign,
+    T: Borrow<[u8]> + 'a,
+{
+    #[inline]
+    fn client(&self) -> &'a Client {
+        self.client
+    }
+}
+
+impl<'a, T, BlockListSet> ContainerNameRequired<'a>
+    for PutBlockListBuilder<'a, T, Yes, BlockListSet>
+where
+    BlockListSet: ToAssign,
+    T: Borrow<[u8]> + 'a,
+{
+    #[inline]
+    fn container_name(&self) -> &'a str {
+        self.container_name.unwrap()
+    }
+}
+
+impl<'a, T, ContainerNameSet> BlockListRequired<'a, T>
+    for PutBlockListBuilder<'a, T, ContainerNameSet, Yes>
+where
+    ContainerNameSet: ToAssign,
+    T: Borrow<[u8]> + 'a,
+{
+    #[inline]
+    fn block_list(&self) -> &'a BlockList<T> {
+        self.block_list.unwrap()
+    }
+}
+
+impl<'a, T, ContainerNameSet, BlockListSet> TimeoutOption
+    for PutBlockListBuilder<'a, T, ContainerNameSet, BlockListSet>
+where
+    ContainerNameSet: ToAssign,
+    BlockListSet: ToAssign,
+    T: Borrow<[u8]> + 'a,
+{
+    #[inline]
+    fn timeout(&self) -> Option<u64> {
+        self.timeout
+    }
+}
+
+impl<'a, T, ContainerNameSet, BlockListSet> ClientRequestIdOption<'a>
+    for PutBlockListBuilder<'a, T, ContainerNameSet, BlockListSet>
+where
+    ContainerNameSet: ToAssign,
+    BlockListSet: ToAssign,
+    T: Borrow<[u8]> + 'a,
+{
+    #[inline]
+    fn client_request_id(&self) -> Option<&'a str> {
+        self.client_request_id
+    }
+}
+
+impl<'a, T, BlockListSet> ContainerNameSupport<'a> for PutBlockListBuilder<'a, T, No, BlockListSet>
+where
+    BlockListSet: ToAssign,
+    T: Borrow<[u8]> + 'a,
+{
+    type O = PutBlockListBuilder<'a, T, Yes, BlockListSet>;
+    #[inline]
+    fn with_container_name(self, container_name: &'a str) -> Self::O {
+        PutBlockListBuilder {
+            client: self.client,
+            p_container_name: PhantomData {},
+            p_block_list: PhantomData {},
+            container_name: Some(container_name),
+            block_list: self.block_list,
+            timeout: self.timeout,
+            client_request_id: self.client_request_id,
+        }
+    }
+}
+
+impl<'a, T, ContainerNameSet> BlockListSupport<'a, T>
+    for PutBlockListBuilder<'a, T, ContainerNameSet, No>
+where
+    ContainerNameSet: ToAssign,
+    T: Borrow<[u8]> + 'a,
+{
+    type O = PutBlockListBuilder<'a, T, ContainerNameSet, Yes>;
+    #[inline]
+    fn with_block_list(self, block_list: &'a BlockList<T>) -> Self::O {
+        PutBlockListBuilder {
+            client: self.client,
+            p_container_name: PhantomData {},
+            p_block_list: PhantomData {},
+            container_name: self.container_name,
+            block_list: Some(block_list),
+            timeout: self.timeout,
+            client_request_id: self.client_request_id,
+        }
+    }
+}
+
+impl<'a, T, ContainerNameSet, BlockListSet> TimeoutSupport
+    for PutBlockListBuilder<'a, T, ContainerNameSet, BlockListSet>
+where
+    ContainerNameSet: ToAssign,
+    BlockListSet: ToAssign,
+    T: Borrow<[u8]> + 'a,
+{
+    type O = PutBlockListBuilder<'a, T, ContainerNameSet, BlockListSet>;
+    #[inline]
+    fn with_timeout(self, timeout: u64) -> Self::O {
+        PutBlockListBuilder {
+            client: self.client,
+            p_container_name: PhantomData {},
+            p_block_list: PhantomData {},
+            container_name: self.container_name,
+            block_list: self.block_list,
+            timeout: Some(timeout),
+            client_request_id: self.client_request_id,
+        }
+    }
+}
+
+impl<'a, T, ContainerNameSet, BlockListSet> ClientRequestIdSupport<'a>
+    for PutBlockListBuilder<'a, T, ContainerNameSet, BlockListSet>
+where
+    ContainerNameSet: ToAssign,
+    BlockListSet: ToAssign,
+    T: Borrow<[u8]> + 'a,
+{
+    type O = PutBlockListBuilder<'a, T, ContainerNameSet, BlockListSet>;
+    #[inline]
+    fn with_client_request_id(self, client_request_id: &'a str) -> Self::O {
+        PutBlockListBuilder {
+            client: self.client,
+            p_container_name: PhantomData {},
+            p_block_list: PhantomData {},
+            container_name: self.container_name,
+            block_list: self.block_list,
+            timeout: self.timeout,
+            client_request_id: Some(client_request_id),
+        }
+    }
+}
+
+// methods callable regardless
+impl<'a, T, ContainerNameSet, BlockListSet>
+    PutBlockListBuilder<'a, T, ContainerNameSet, BlockListSet>
+where
+    ContainerNameSet: ToAssign,
+    BlockListSet: ToAssign,
+    T: Borrow<[u8]> + 'a,
+{
+}
+
+// methods callable only when every mandatory field has been filled
+impl<'a, T> PutBlockListBuilder<'a, T, Yes, Yes>
+where
+    T: Borrow<[u8]> + 'a,
+{
+    #[inline]
+    pub fn finalize(self) -> impl Future<Item = PutBlockListResponse, Error = AzureError> {
+        todo!()
+    }
+}
@@ -0,0 +1,194 @@
+// This file is @generated by bpb, do not edit it by hand.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+// getter and setter traits
+pub trait ClientRequired<'a> {
+    fn client(&self) -> &'a Client;
+}
+
+pub trait MetadataRequired {
+    fn metadata(&self) -> &HashMap<String, String>;
+}
+
+pub trait MetadataSupport {
+    type O;
+    fn with_metadata(self, metadata: HashMap<String, String>) -> Self::O;
+}
+
+pub struct SetBlobMetadataBuilder<'a, ContainerNameSet, MetadataSet>
+where
+    ContainerNameSet: ToAssign,
+    MetadataSet: ToAssign,
+{
+    client: &'a Client,
+    p_container_name: PhantomData<ContainerNameSet>,
+    p_metadata: PhantomData<MetadataSet>,
+    container_name: Option<&'a str>,
+    metadata: HashMap<String, String>,
+    tags: BTreeMap<String, String>,
+    snapshots: Vec<&'a str>,
+}
+
+impl<'a> SetBlobMetadataBuilder<'a, No, No> {
+    pub(crate) fn new(client: &'a Client) -> SetBlobMetadataBuilder<'a, No, No> {
+        SetBlobMetadataBuilder {
+            client,
+            p_container_name: PhantomData {},
+            container_name: None,
+            p_metadata: PhantomData {},
+            metadata: Default::default(),
+            tags: Default::default(),
+            snapshots: Default::default(),
+        }
+    }
+}
+
+impl<'a, ContainerNameSet, MetadataSet> ClientRequired<'a>
+    for SetBlobMetadataBuilder<'a, ContainerNameSet, MetadataSet>
+where
+    ContainerNameSet: ToAssign,
+    MetadataSet: ToAssign,
+{
+    fn client(&self) -> &'a Client {
+        self.client
+    }
+}
+
+// get mandatory no traits methods
+impl<'a, MetadataSet> SetBlobMetadataBuilder<'a, Yes, MetadataSet>
+where
+    MetadataSet: ToAssign,
+{
+    pub fn container_name(&self) -> &'a str {
+        self.container_name.unwrap()
+    }
+}
+
+// set mandatory no traits methods
+impl<'a, MetadataSet> SetBlobMetadataBuilder<'a, No, MetadataSet>
+where
+    MetadataSet: ToAssign,
+{
+    pub fn with_container_name(
+        self,
+        container_name: &'a str,
+    ) -> SetBlobMetadataBuilder<'a, Yes, MetadataSet> {
+        SetBlobMetadataBuilder {
+            client: self.client,
+            p_container_name: PhantomData {},
+            p_metadata: PhantomData {},
+            container_name: Some(container_name),
+            metadata: self.metadata,
+            tags: self.tags,
+            snapshots: self.snapshots,
+        }
+    }
+}
+
+impl<'a, ContainerNameSet> MetadataRequired for SetBlobMetadataBuilder<'a, ContainerNameSet, Yes>
+where
+    ContainerNameSet: ToAssign,
+{
+    fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+}
+
+impl<'a, ContainerNameSet> MetadataSupport for SetBlobMetadataBuilder<'a, ContainerNameSet, No>
+where
+    ContainerNameSet: ToAssign,
+{
+    type O = SetBlobMetadataBuilder<'a, ContainerNameSet, Yes>;
+    fn with_metadata(self, metadata: HashMap<String, String>) -> Self::O {
+        SetBlobMetadataBuilder {
+            client: self.client,
+            p_container_name: PhantomData {},
+            p_metadata: PhantomData {},
+            container_name: self.container_name,
+            metadata,
+            tags: self.tags,
+            snapshots: self.snapshots,
+        }
+    }
+}
+
+// methods callable regardless
+impl<'a, ContainerNameSet, MetadataSet> SetBlobMetadataBuilder<'a, ContainerNameSet, MetadataSet>
+where
+    ContainerNameSet: ToAssign,
+    MetadataSet: ToAssign,
+{
+    pub fn tags(&self) -> &BTreeMap<String, String> {
+        &self.tags
+    }
+    pub fn snapshots(&self) -> &Vec<&'a str> {
+        &self.snapshots
+    }
+    pub fn with_tags(self, tags: BTreeMap<String, String>) -> Self {
+        SetBlobMetadataBuilder {
+            client: self.client,
+            p_container_name: self.p_container_name,
+            p_metadata: self.p_metadata,
+            container_name: self.container_name,
+            metadata: self.metadata,
+            tags,
+            snapshots: self.snapshots,
+        }
+    }
+    pub fn with_snapshots(self, snapshots: Vec<&'a str>) -> Self {
+        SetBlobMetadataBuilder {
+            client: self.client,
+            p_container_name: self.p_container_name,
+            p_metadata: self.p_metadata,
+            container_name: self.container_name,
+            metadata: self.metadata,
+            tags: self.tags,
+            snapshots,
+        }
+    }
+    pub fn add_metadata(
+        mut self,
+        key: String,
+        value: String,
+    ) -> SetBlobMetadataBuilder<'a, ContainerNameSet, Yes> {
+        self.metadata.insert(key, value);
+        SetBlobMetadataBuilder {
+            client: self.client,
+            p_container_name: PhantomData {},
+            p_metadata: PhantomData {},
+            container_name: self.container_name,
+            metadata: self.metadata,
+            tags: self.tags,
+            snapshots: self.snapshots,
+        }
+    }
+    pub fn extend_metadata<I: IntoIterator<Item = (String, String)>>(mut self, items: I) -> Self {
+        self.metadata.extend(items);
+        self
+    }
+    pub fn add_tags(mut self, key: String, value: String) -> Self {
+        self.tags.insert(key, value);
+        self
+    }
+    pub fn extend_tags<I: IntoIterator<Item = (String, String)>>(mut self, items: I) -> Self {
+        self.tags.extend(items);
+        self
+    }
+    pub fn add_snapshots(mut self, item: &'a str) -> Self {
+        self.snapshots.push(item);
+        self
+    }
+    pub fn extend_snapshots<I: IntoIterator<Item = &'a str>>(mut self, items: I) -> Self {
+        self.snapshots.extend(items);
+        self
+    }
+}
+
+// methods callable only when every mandatory field has been filled
+impl<'a> SetBlobMetadataBuilder<'a, Yes, Yes> {
+    pub fn headers(&self) -> usize {
+        self.metadata.len() + self.tags.len() + self.snapshots.len()
+    }
+}
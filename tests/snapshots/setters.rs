@@ -0,0 +1,121 @@
+// This file is @generated by bpb, do not edit it by hand.
+
+pub struct PutBlobBuilder<'a, ContainerNameSet, BlobNameSet>
+where
+    ContainerNameSet: ToAssign,
+    BlobNameSet: ToAssign,
+{
+    client: &'a Client,
+    p_container_name: PhantomData<ContainerNameSet>,
+    p_blob_name: PhantomData<BlobNameSet>,
+    container_name: Option<String>,
+    blob_name: Option<&'a str>,
+    number_of_things: Option<u8>,
+}
+
+impl<'a> PutBlobBuilder<'a, No, No> {
+    pub(crate) fn new(client: &'a Client) -> PutBlobBuilder<'a, No, No> {
+        PutBlobBuilder {
+            client,
+            p_container_name: PhantomData {},
+            container_name: None,
+            p_blob_name: PhantomData {},
+            blob_name: None,
+            number_of_things: None,
+        }
+    }
+}
+
+impl<'a, ContainerNameSet, BlobNameSet> ClientRequired<'a>
+    for PutBlobBuilder<'a, ContainerNameSet, BlobNameSet>
+where
+    ContainerNameSet: ToAssign,
+    BlobNameSet: ToAssign,
+{
+    fn client(&self) -> &'a Client {
+        self.client
+    }
+}
+
+// get mandatory no traits methods
+impl<'a, ContainerNameSet> PutBlobBuilder<'a, ContainerNameSet, Yes>
+where
+    ContainerNameSet: ToAssign,
+{
+    pub fn blob_name(&self) -> &'a str {
+        self.blob_name.unwrap()
+    }
+}
+
+// set mandatory no traits methods
+impl<'a, ContainerNameSet> PutBlobBuilder<'a, ContainerNameSet, No>
+where
+    ContainerNameSet: ToAssign,
+{
+    pub fn with_blob_name<V: AsRef<str> + ?Sized>(
+        self,
+        blob_name: &'a V,
+    ) -> PutBlobBuilder<'a, ContainerNameSet, Yes> {
+        let blob_name: &'a str = AsRef::<str>::as_ref(blob_name);
+        PutBlobBuilder {
+            client: self.client,
+            p_container_name: PhantomData {},
+            p_blob_name: PhantomData {},
+            container_name: self.container_name,
+            blob_name: Some(blob_name),
+            number_of_things: self.number_of_things,
+        }
+    }
+}
+
+impl<'a, BlobNameSet> ContainerNameRequired for PutBlobBuilder<'a, Yes, BlobNameSet>
+where
+    BlobNameSet: ToAssign,
+{
+    fn container_name(&self) -> String {
+        self.container_name.clone().unwrap()
+    }
+}
+
+impl<'a, BlobNameSet> ContainerNameSupport for PutBlobBuilder<'a, No, BlobNameSet>
+where
+    BlobNameSet: ToAssign,
+{
+    type O = PutBlobBuilder<'a, Yes, BlobNameSet>;
+    fn with_container_name<V: Into<String>>(self, container_name: V) -> Self::O {
+        let container_name: String = container_name.into();
+        PutBlobBuilder {
+            client: self.client,
+            p_container_name: PhantomData {},
+            p_blob_name: PhantomData {},
+            container_name: Some(container_name),
+            blob_name: self.blob_name,
+            number_of_things: self.number_of_things,
+        }
+    }
+}
+
+// methods callable regardless
+impl<'a, ContainerNameSet, BlobNameSet> PutBlobBuilder<'a, ContainerNameSet, BlobNameSet>
+where
+    ContainerNameSet: ToAssign,
+    BlobNameSet: ToAssign,
+{
+    pub fn number_of_things(&self) -> Option<u8> {
+        self.number_of_things
+    }
+    pub fn with_number_of_things(self, number_of_things: impl Into<u8>) -> Self {
+        let number_of_things: u8 = number_of_things.into();
+        PutBlobBuilder {
+            client: self.client,
+            p_container_name: self.p_container_name,
+            p_blob_name: self.p_blob_name,
+            container_name: self.container_name,
+            blob_name: self.blob_name,
+            number_of_things: Some(number_of_things),
+        }
+    }
+}
+
+// methods callable only when every mandatory field has been filled
+impl<'a> PutBlobBuilder<'a, Yes, Yes> {}
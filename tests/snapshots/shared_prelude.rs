@@ -0,0 +1,119 @@
+// This file is @generated by bpb, do not edit it by hand.
+
+use crate::ts::{No, ToAssign, Yes};
+use std::marker::PhantomData;
+use std::time::Duration;
+
+pub struct GetQueueBuilder<QueueNameSet>
+where
+    QueueNameSet: ToAssign,
+{
+    p_queue_name: PhantomData<QueueNameSet>,
+    queue_name: Option<&'static str>,
+    timeout: Option<Duration>,
+}
+
+impl GetQueueBuilder<No> {
+    pub(crate) fn new() -> GetQueueBuilder<No> {
+        GetQueueBuilder {
+            p_queue_name: PhantomData {},
+            queue_name: None,
+            timeout: None,
+        }
+    }
+}
+
+// get mandatory no traits methods
+impl GetQueueBuilder<Yes> {
+    pub fn queue_name(&self) -> &'static str {
+        self.queue_name.unwrap()
+    }
+}
+
+// set mandatory no traits methods
+impl GetQueueBuilder<No> {
+    pub fn with_queue_name(self, queue_name: &'static str) -> GetQueueBuilder<Yes> {
+        GetQueueBuilder {
+            p_queue_name: PhantomData {},
+            queue_name: Some(queue_name),
+            timeout: self.timeout,
+        }
+    }
+}
+
+// methods callable regardless
+impl<QueueNameSet> GetQueueBuilder<QueueNameSet>
+where
+    QueueNameSet: ToAssign,
+{
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        GetQueueBuilder {
+            p_queue_name: self.p_queue_name,
+            queue_name: self.queue_name,
+            timeout: Some(timeout),
+        }
+    }
+}
+
+// methods callable only when every mandatory field has been filled
+impl GetQueueBuilder<Yes> {}
+
+pub struct PutMessageBuilder<MessageSet>
+where
+    MessageSet: ToAssign,
+{
+    p_message: PhantomData<MessageSet>,
+    message: Option<&'static str>,
+    visibility_timeout: Option<Duration>,
+}
+
+impl PutMessageBuilder<No> {
+    pub(crate) fn new() -> PutMessageBuilder<No> {
+        PutMessageBuilder {
+            p_message: PhantomData {},
+            message: None,
+            visibility_timeout: None,
+        }
+    }
+}
+
+// get mandatory no traits methods
+impl PutMessageBuilder<Yes> {
+    pub fn message(&self) -> &'static str {
+        self.message.unwrap()
+    }
+}
+
+// set mandatory no traits methods
+impl PutMessageBuilder<No> {
+    pub fn with_message(self, message: &'static str) -> PutMessageBuilder<Yes> {
+        PutMessageBuilder {
+            p_message: PhantomData {},
+            message: Some(message),
+            visibility_timeout: self.visibility_timeout,
+        }
+    }
+}
+
+// methods callable regardless
+impl<MessageSet> PutMessageBuilder<MessageSet>
+where
+    MessageSet: ToAssign,
+{
+    pub fn visibility_timeout(&self) -> Option<Duration> {
+        self.visibility_timeout
+    }
+    pub fn with_visibility_timeout(self, visibility_timeout: Duration) -> Self {
+        PutMessageBuilder {
+            p_message: self.p_message,
+            message: self.message,
+            visibility_timeout: Some(visibility_timeout),
+        }
+    }
+}
+
+// methods callable only when every mandatory field has been filled
+impl PutMessageBuilder<Yes> {}
@@ -0,0 +1,52 @@
+// This file is @generated by bpb, do not edit it by hand.
+
+pub struct SaratogaBuilder<'a, ContainerNameSet>
+where
+    ContainerNameSet: ToAssign,
+{
+    client: &'a Client,
+    p_container_name: PhantomData<ContainerNameSet>,
+    container_name: Option<String>,
+}
+
+impl<'a> SaratogaBuilder<'a, No> {
+    pub(crate) fn new(client: &'a Client) -> SaratogaBuilder<'a, No> {
+        SaratogaBuilder {
+            client,
+            p_container_name: PhantomData {},
+            container_name: None,
+        }
+    }
+}
+
+impl<'a, ContainerNameSet> ClientRequired<'a> for SaratogaBuilder<'a, ContainerNameSet>
+where
+    ContainerNameSet: ToAssign,
+{
+    fn client(&self) -> &'a Client {
+        self.client
+    }
+}
+
+impl<'a> ContainerNameRequired for SaratogaBuilder<'a, Yes> {
+    fn container_name(&self) -> String {
+        self.container_name.clone().unwrap()
+    }
+}
+
+impl<'a> ContainerNameSupport for SaratogaBuilder<'a, No> {
+    type O = SaratogaBuilder<'a, Yes>;
+    fn with_container_name(self, container_name: String) -> Self::O {
+        SaratogaBuilder {
+            client: self.client,
+            p_container_name: PhantomData {},
+            container_name: Some(container_name),
+        }
+    }
+}
+
+// methods callable regardless
+impl<'a, ContainerNameSet> SaratogaBuilder<'a, ContainerNameSet> where ContainerNameSet: ToAssign {}
+
+// methods callable only when every mandatory field has been filled
+impl<'a> SaratogaBuilder<'a, Yes> {}
@@ -0,0 +1,172 @@
+// This file is @generated by bpb, do not edit it by hand.
+
+pub struct SaratogaBuilder<'a, P, BlobPathSet, NumberOfThingsSet>
+where
+    BlobPathSet: ToAssign,
+    NumberOfThingsSet: ToAssign,
+    P: IntoAzurePath + Clone,
+{
+    client: &'a Client,
+    constro: &'a u64,
+    p_blob_path: PhantomData<BlobPathSet>,
+    p_number_of_things: PhantomData<NumberOfThingsSet>,
+    blob_path: Option<P>,
+    client_request_id: Option<&'a str>,
+    number_of_things: u8,
+    optional_no_trait: Option<&'a str>,
+}
+
+impl<'a, P> SaratogaBuilder<'a, P, No, No>
+where
+    P: IntoAzurePath + Clone,
+{
+    pub(crate) fn new(client: &'a Client, constro: &'a u64) -> SaratogaBuilder<'a, P, No, No> {
+        SaratogaBuilder {
+            client,
+            constro,
+            p_blob_path: PhantomData {},
+            blob_path: None,
+            p_number_of_things: PhantomData {},
+            number_of_things: 0,
+            client_request_id: None,
+            optional_no_trait: None,
+        }
+    }
+}
+
+impl<'a, P, BlobPathSet, NumberOfThingsSet> ClientRequired<'a>
+    for SaratogaBuilder<'a, P, BlobPathSet, NumberOfThingsSet>
+where
+    BlobPathSet: ToAssign,
+    NumberOfThingsSet: ToAssign,
+    P: IntoAzurePath + Clone,
+{
+    fn client(&self) -> &'a Client {
+        self.client
+    }
+}
+
+impl<'a, P, NumberOfThingsSet> BlobPathRequired<P>
+    for SaratogaBuilder<'a, P, Yes, NumberOfThingsSet>
+where
+    NumberOfThingsSet: ToAssign,
+    P: IntoAzurePath + Clone,
+{
+    fn blob_path(&self) -> P {
+        self.blob_path.clone().unwrap()
+    }
+}
+
+impl<'a, P, BlobPathSet, NumberOfThingsSet> ClientRequestIdOption<'a>
+    for SaratogaBuilder<'a, P, BlobPathSet, NumberOfThingsSet>
+where
+    BlobPathSet: ToAssign,
+    NumberOfThingsSet: ToAssign,
+    P: IntoAzurePath + Clone,
+{
+    fn client_request_id(&self) -> Option<&'a str> {
+        self.client_request_id
+    }
+}
+
+impl<'a, P, BlobPathSet> NumberOfThingsRequired for SaratogaBuilder<'a, P, BlobPathSet, Yes>
+where
+    BlobPathSet: ToAssign,
+    P: IntoAzurePath + Clone,
+{
+    fn number_of_things(&self) -> u8 {
+        self.number_of_things
+    }
+}
+
+impl<'a, P, NumberOfThingsSet> BlobPathSupport<P> for SaratogaBuilder<'a, P, No, NumberOfThingsSet>
+where
+    NumberOfThingsSet: ToAssign,
+    P: IntoAzurePath + Clone,
+{
+    type O = SaratogaBuilder<'a, P, Yes, NumberOfThingsSet>;
+    fn with_blob_path(self, blob_path: P) -> Self::O {
+        SaratogaBuilder {
+            client: self.client,
+            constro: self.constro,
+            p_blob_path: PhantomData {},
+            p_number_of_things: PhantomData {},
+            blob_path: Some(blob_path),
+            client_request_id: self.client_request_id,
+            number_of_things: self.number_of_things,
+            optional_no_trait: self.optional_no_trait,
+        }
+    }
+}
+
+impl<'a, P, BlobPathSet, NumberOfThingsSet> ClientRequestIdSupport<'a>
+    for SaratogaBuilder<'a, P, BlobPathSet, NumberOfThingsSet>
+where
+    BlobPathSet: ToAssign,
+    NumberOfThingsSet: ToAssign,
+    P: IntoAzurePath + Clone,
+{
+    type O = SaratogaBuilder<'a, P, BlobPathSet, NumberOfThingsSet>;
+    fn with_client_request_id(self, client_request_id: &'a str) -> Self::O {
+        SaratogaBuilder {
+            client: self.client,
+            constro: self.constro,
+            p_blob_path: PhantomData {},
+            p_number_of_things: PhantomData {},
+            blob_path: self.blob_path,
+            client_request_id: Some(client_request_id),
+            number_of_things: self.number_of_things,
+            optional_no_trait: self.optional_no_trait,
+        }
+    }
+}
+
+impl<'a, P, BlobPathSet> NumberOfThingsSupport for SaratogaBuilder<'a, P, BlobPathSet, No>
+where
+    BlobPathSet: ToAssign,
+    P: IntoAzurePath + Clone,
+{
+    type O = SaratogaBuilder<'a, P, BlobPathSet, Yes>;
+    fn with_number_of_things(self, number_of_things: u8) -> Self::O {
+        SaratogaBuilder {
+            client: self.client,
+            constro: self.constro,
+            p_blob_path: PhantomData {},
+            p_number_of_things: PhantomData {},
+            blob_path: self.blob_path,
+            client_request_id: self.client_request_id,
+            number_of_things,
+            optional_no_trait: self.optional_no_trait,
+        }
+    }
+}
+
+// methods callable regardless
+impl<'a, P, BlobPathSet, NumberOfThingsSet> SaratogaBuilder<'a, P, BlobPathSet, NumberOfThingsSet>
+where
+    BlobPathSet: ToAssign,
+    NumberOfThingsSet: ToAssign,
+    P: IntoAzurePath + Clone,
+{
+    pub fn constro(&self) -> &'a u64 {
+        self.constro
+    }
+    pub fn optional_no_trait(&self) -> Option<&'a str> {
+        self.optional_no_trait
+    }
+    pub fn with_optional_no_trait(self, optional_no_trait: &'a str) -> Self {
+        SaratogaBuilder {
+            client: self.client,
+            constro: self.constro,
+            p_blob_path: self.p_blob_path,
+            p_number_of_things: self.p_number_of_things,
+            blob_path: self.blob_path,
+            client_request_id: self.client_request_id,
+            number_of_things: self.number_of_things,
+            optional_no_trait: Some(optional_no_trait),
+        }
+    }
+}
+
+// methods callable only when every mandatory field has been filled
+impl<'a, P> SaratogaBuilder<'a, P, Yes, Yes> where P: IntoAzurePath + Clone {}
@@ -0,0 +1,22 @@
+// This file is @generated by bpb, do not edit it by hand.
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for super::Set {}
+    impl Sealed for super::Unset {}
+}
+
+/// Implemented by the typestates of the builders.
+pub trait ToAssign: private::Sealed {}
+
+/// The typestate of a required field that has been set.
+#[derive(Debug, Clone, Copy)]
+pub enum Set {}
+
+/// The typestate of a required field that has not been set yet.
+#[derive(Debug, Clone, Copy)]
+pub enum Unset {}
+
+impl ToAssign for Set {}
+
+impl ToAssign for Unset {}
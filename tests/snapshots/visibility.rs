@@ -0,0 +1,85 @@
+// This file is @generated by bpb, do not edit it by hand.
+
+pub(crate) struct GetBlobBuilder<'a, BlobNameSet>
+where
+    BlobNameSet: ToAssign,
+{
+    client: &'a Client,
+    snapshot: u64,
+    p_blob_name: PhantomData<BlobNameSet>,
+    blob_name: Option<&'a str>,
+    timeout: Option<u64>,
+}
+
+impl<'a> GetBlobBuilder<'a, No> {
+    pub(crate) fn new(client: &'a Client, snapshot: u64) -> GetBlobBuilder<'a, No> {
+        GetBlobBuilder {
+            client,
+            snapshot,
+            p_blob_name: PhantomData {},
+            blob_name: None,
+            timeout: None,
+        }
+    }
+}
+
+impl<'a, BlobNameSet> ClientRequired<'a> for GetBlobBuilder<'a, BlobNameSet>
+where
+    BlobNameSet: ToAssign,
+{
+    fn client(&self) -> &'a Client {
+        self.client
+    }
+}
+
+// get mandatory no traits methods
+impl<'a> GetBlobBuilder<'a, Yes> {
+    pub(crate) fn blob_name(&self) -> &'a str {
+        self.blob_name.unwrap()
+    }
+}
+
+// set mandatory no traits methods
+impl<'a> GetBlobBuilder<'a, No> {
+    pub(crate) fn with_blob_name(self, blob_name: &'a str) -> GetBlobBuilder<'a, Yes> {
+        GetBlobBuilder {
+            client: self.client,
+            snapshot: self.snapshot,
+            p_blob_name: PhantomData {},
+            blob_name: Some(blob_name),
+            timeout: self.timeout,
+        }
+    }
+}
+
+// methods callable regardless
+impl<'a, BlobNameSet> GetBlobBuilder<'a, BlobNameSet>
+where
+    BlobNameSet: ToAssign,
+{
+    pub fn snapshot(&self) -> u64 {
+        self.snapshot
+    }
+    fn timeout(&self) -> Option<u64> {
+        self.timeout
+    }
+    fn with_timeout(self, timeout: u64) -> Self {
+        GetBlobBuilder {
+            client: self.client,
+            snapshot: self.snapshot,
+            p_blob_name: self.p_blob_name,
+            blob_name: self.blob_name,
+            timeout: Some(timeout),
+        }
+    }
+}
+
+// methods callable only when every mandatory field has been filled
+impl<'a> GetBlobBuilder<'a, Yes> {
+    pub(crate) fn finalize(self) -> u64 {
+        self.timeout.unwrap_or(30)
+    }
+    pub fn into_blob_name(self) -> &'a str {
+        self.blob_name.unwrap()
+    }
+}
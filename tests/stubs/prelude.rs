@@ -0,0 +1,94 @@
+//! Stand-ins for the items the example builders expect to find in scope,
+//! so that their generated code can be compiled by the `compile` tests.
+
+#![allow(dead_code, unused_imports)]
+
+pub use std::borrow::Borrow;
+pub use std::marker::PhantomData;
+
+pub trait ToAssign {}
+#[derive(Debug, Clone, Copy)]
+pub struct Yes {}
+#[derive(Debug, Clone, Copy)]
+pub struct No {}
+impl ToAssign for Yes {}
+impl ToAssign for No {}
+
+pub struct Client;
+
+pub trait IntoAzurePath {
+    fn container_name(&self) -> &str;
+    fn blob_name(&self) -> &str;
+}
+
+#[derive(Clone)]
+pub struct BlobPath(pub &'static str, pub &'static str);
+
+impl IntoAzurePath for BlobPath {
+    fn container_name(&self) -> &str {
+        self.0
+    }
+
+    fn blob_name(&self) -> &str {
+        self.1
+    }
+}
+
+pub struct BlockList<T> {
+    pub blocks: Vec<T>,
+}
+
+pub struct PutBlockListResponse;
+pub struct AzureError;
+
+// a futures 0.1 style `Future`, implemented by everything so that the
+// `todo!()` body of a finalizer type checks
+pub trait Future {
+    type Item;
+    type Error;
+}
+
+impl<T> Future for T {
+    type Item = PutBlockListResponse;
+    type Error = AzureError;
+}
+
+pub trait ClientRequired<'a> {
+    fn client(&self) -> &'a Client;
+}
+
+pub trait TimeoutOption {
+    fn timeout(&self) -> Option<u64>;
+}
+
+pub trait TimeoutSupport {
+    type O;
+    fn with_timeout(self, timeout: u64) -> Self::O;
+}
+
+pub trait ClientRequestIdOption<'a> {
+    fn client_request_id(&self) -> Option<&'a str>;
+}
+
+pub trait ClientRequestIdSupport<'a> {
+    type O;
+    fn with_client_request_id(self, client_request_id: &'a str) -> Self::O;
+}
+
+pub trait ContainerNameRequired<'a> {
+    fn container_name(&self) -> &'a str;
+}
+
+pub trait ContainerNameSupport<'a> {
+    type O;
+    fn with_container_name(self, container_name: &'a str) -> Self::O;
+}
+
+pub trait BlockListRequired<'a, T> {
+    fn block_list(&self) -> &'a BlockList<T>;
+}
+
+pub trait BlockListSupport<'a, T> {
+    type O;
+    fn with_block_list(self, block_list: &'a BlockList<T>) -> Self::O;
+}
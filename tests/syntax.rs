@@ -0,0 +1,134 @@
+//! Loads `test.json` rewritten in every other supported syntax from
+//! `tests/syntax` and checks that each generates the same builder.
+
+extern crate bpb;
+
+use std::fs;
+use std::path::PathBuf;
+
+fn check_same_as_json(file: &str) {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let expected = bpb::generate(&bpb::load(root.join("test.json")).unwrap()).unwrap();
+
+    let stc = bpb::load(root.join("tests/syntax").join(file)).unwrap();
+    assert_eq!(bpb::generate(&stc).unwrap(), expected, "{}", file);
+}
+
+#[test]
+fn json5() {
+    check_same_as_json("test.json5");
+}
+
+#[test]
+fn toml() {
+    check_same_as_json("test.toml");
+}
+
+#[test]
+fn yaml() {
+    check_same_as_json("test.yaml");
+}
+
+#[test]
+fn ron() {
+    check_same_as_json("test.ron");
+}
+
+#[test]
+fn syntax_errors_are_located() {
+    let text = "name = \"SaratogaBuilder\"\nfields = = []\n";
+    match bpb::from_str_syntax(text, bpb::Syntax::Toml) {
+        Err(bpb::Error::Invalid(diagnostics)) => {
+            assert_eq!(diagnostics.len(), 1);
+            assert_eq!(diagnostics[0].location.map(|l| l.line), Some(2));
+        }
+        other => panic!("expected a located syntax error, got {:?}", other),
+    }
+}
+
+// the third field reuses the builder type of the first, which is reported at
+// the `builder_type` key of the third field
+fn check_validation_located(file: &str, syntax: bpb::Syntax) {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let text = fs::read_to_string(root.join("tests/syntax").join(file))
+        .unwrap()
+        .replace("NumberOfThingsSet", "BlobPathSet");
+
+    let (line, builder_type) = text
+        .lines()
+        .enumerate()
+        .filter(|&(_, l)| l.contains("BlobPathSet"))
+        .nth(1)
+        .unwrap();
+    let column = builder_type.find("builder_type").unwrap();
+
+    match bpb::from_str_syntax(&text, syntax) {
+        Err(bpb::Error::Invalid(diagnostics)) => {
+            assert_eq!(diagnostics.len(), 1, "{}", file);
+            assert_eq!(
+                diagnostics[0].to_string(),
+                format!(
+                    "{}:{}: fields[2].builder_type: builder type `BlobPathSet` is already used by fields[0].builder_type",
+                    line + 1,
+                    column + 1
+                ),
+                "{}",
+                file
+            );
+        }
+        other => panic!("expected diagnostics for {}, got {:?}", file, other),
+    }
+}
+
+#[test]
+fn validation_problems_are_located() {
+    check_validation_located("test.json5", bpb::Syntax::Json5);
+    check_validation_located("test.toml", bpb::Syntax::Toml);
+    check_validation_located("test.yaml", bpb::Syntax::Yaml);
+    check_validation_located("test.ron", bpb::Syntax::Ron);
+}
+
+#[test]
+fn type_errors_are_located() {
+    // `optional` takes a boolean; the parsers report no path, which is
+    // left out
+    let cases = [
+        (
+            bpb::Syntax::Json,
+            "{\n  \"name\": \"B\",\n  \"fields\": [\n    { \"name\": \"a\", \"field_type\": \"u8\", \"optional\": \"no\" }\n  ]\n}\n",
+            "4:55: invalid type: string \"no\", expected a boolean",
+        ),
+        (
+            bpb::Syntax::Json5,
+            "{\n  name: 'B',\n  fields: [\n    { name: 'a', field_type: 'u8', optional: 'no' },\n  ],\n}\n",
+            "4:46: invalid type: string \"no\", expected a boolean",
+        ),
+        (
+            bpb::Syntax::Toml,
+            "name = \"B\"\n\n[[fields]]\nname = \"a\"\nfield_type = \"u8\"\noptional = \"no\"\n",
+            "6:12: invalid type: string \"no\", expected a boolean",
+        ),
+        (
+            bpb::Syntax::Yaml,
+            "name: B\nfields:\n  - name: a\n    field_type: u8\n    optional: \"no\"\n",
+            "5:15: invalid type: string \"no\", expected a boolean",
+        ),
+        (
+            bpb::Syntax::Ron,
+            "(\n  name: \"B\",\n  fields: [\n    (name: \"a\", field_type: \"u8\", optional: \"no\"),\n  ],\n)\n",
+            "4:44: Expected boolean",
+        ),
+    ];
+    for &(syntax, text, expected) in cases.iter() {
+        match bpb::from_str_syntax(text, syntax) {
+            Err(bpb::Error::Invalid(diagnostics)) => {
+                assert_eq!(diagnostics.len(), 1, "{:?}", syntax);
+                assert_eq!(diagnostics[0].to_string(), expected, "{:?}", syntax);
+            }
+            other => panic!(
+                "expected a located type error in {:?}, got {:?}",
+                syntax, other
+            ),
+        }
+    }
+}
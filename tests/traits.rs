@@ -0,0 +1,68 @@
+//! The trait definitions emitted for a builder only carry the bounds and
+//! `extra_wheres` that refer to the generic parameters of the trait.
+
+extern crate bpb;
+extern crate syn;
+
+#[test]
+fn wheres_only_refer_to_the_parameters_of_the_trait() {
+    let stc = bpb::from_str(
+        r#"{
+            "name": "PutBlockBuilder",
+            "extra_types": ["'a", "'b", { "type": "S", "bounds": ["AsRef<str>", "'b"] }, "T"],
+            "extra_wheres": ["T: Borrow<[u8]> + 'a", "'b: 'a", "T: Clone", "Vec<T>: Debug"],
+            "emit_traits": true,
+            "fields": [
+                {
+                    "name": "block",
+                    "field_type": "T",
+                    "builder_type": "BlockSet",
+                    "trait_get": "BlockRequired<T>",
+                    "trait_set": "BlockSupport<T>"
+                },
+                {
+                    "name": "lease_id",
+                    "field_type": "&'b str",
+                    "optional": true,
+                    "trait_get": "LeaseIdOption<'b>",
+                    "trait_set": "LeaseIdSupport<'b>"
+                },
+                {
+                    "name": "source",
+                    "field_type": "&'a T",
+                    "optional": true,
+                    "trait_get": "SourceOption<'a, T>",
+                    "trait_set": "SourceSupport<'a, T>"
+                },
+                {
+                    "name": "name",
+                    "field_type": "S",
+                    "optional": true,
+                    "trait_get": "NameOption<S>",
+                    "trait_set": "NameSupport<S>"
+                }
+            ]
+        }"#,
+    )
+    .unwrap();
+
+    let traits = bpb::collect_traits(&stc).unwrap();
+    let wheres = |name: &str| {
+        traits
+            .iter()
+            .find(|t| t.name == name)
+            .unwrap()
+            .wheres
+            .clone()
+    };
+    assert_eq!(wheres("BlockRequired"), vec!["T: Clone", "Vec<T>: Debug"]);
+    assert_eq!(wheres("LeaseIdOption"), Vec::<String>::new());
+    assert_eq!(
+        wheres("SourceSupport"),
+        vec!["T: Borrow<[u8]> + 'a", "T: Clone", "Vec<T>: Debug"]
+    );
+    // the bound of `S` refers to `'b`, which `NameOption` does not declare
+    assert_eq!(wheres("NameOption"), Vec::<String>::new());
+
+    syn::parse_file(&bpb::generate(&stc).unwrap()).unwrap();
+}
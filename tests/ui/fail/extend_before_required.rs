@@ -0,0 +1,15 @@
+#[path = "../../stubs/prelude.rs"]
+mod stubs;
+use stubs::{Client, No, PhantomData, ToAssign, Yes};
+
+include!("../../snapshots/set_blob_metadata_builder.rs");
+
+fn main() {
+    let client = Client;
+
+    // extending may add nothing, so the metadata is still unset
+    let _ = SetBlobMetadataBuilder::new(&client)
+        .with_container_name("container")
+        .extend_metadata(vec![("owner".to_owned(), "me".to_owned())])
+        .headers();
+}
@@ -0,0 +1,14 @@
+#[path = "../../stubs/prelude.rs"]
+mod stubs;
+use stubs::*;
+
+include!("../../snapshots/put_block_list_builder.rs");
+
+fn main() {
+    let client = Client;
+
+    // the block list is required but has not been set
+    let _ = PutBlockListBuilder::<Vec<u8>, _, _>::new(&client)
+        .with_container_name("container")
+        .finalize();
+}
@@ -0,0 +1,12 @@
+#[path = "../../stubs/prelude.rs"]
+mod stubs;
+use stubs::*;
+
+include!("../../snapshots/change_blob_lease_builder.rs");
+
+fn main() {
+    let client = Client;
+
+    let builder = ChangeBlobLeaseBuilder::new(&client);
+    let _ = builder.container_name();
+}
@@ -0,0 +1,10 @@
+mod ts {
+    include!("../../snapshots/prelude.rs");
+}
+
+// `ToAssign` is sealed: only the markers of the prelude implement it
+struct Mine;
+
+impl ts::ToAssign for Mine {}
+
+fn main() {}
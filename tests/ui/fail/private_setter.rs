@@ -0,0 +1,17 @@
+#[path = "../../stubs/prelude.rs"]
+mod stubs;
+use stubs::*;
+
+mod builder {
+    use super::*;
+
+    include!("../../snapshots/visibility.rs");
+}
+
+use builder::GetBlobBuilder;
+
+fn main() {
+    let client = Client;
+
+    let _ = GetBlobBuilder::new(&client, 7).with_timeout(60);
+}
@@ -0,0 +1,13 @@
+#[path = "../../stubs/prelude.rs"]
+mod stubs;
+use stubs::*;
+
+include!("../../snapshots/change_blob_lease_builder.rs");
+
+fn main() {
+    let client = Client;
+
+    let _ = ChangeBlobLeaseBuilder::new(&client)
+        .with_container_name("container")
+        .with_container_name("another");
+}
@@ -0,0 +1,17 @@
+#[path = "../../stubs/prelude.rs"]
+mod stubs;
+use stubs::*;
+
+include!("../../snapshots/change_blob_lease_builder.rs");
+
+fn main() {
+    let client = Client;
+
+    let builder = ChangeBlobLeaseBuilder::new(&client);
+    assert_eq!(builder.timeout(), None);
+
+    let builder = builder.with_container_name("container").with_timeout(30);
+    assert_eq!(builder.container_name(), "container");
+    assert_eq!(builder.timeout(), Some(30));
+    assert_eq!(builder.client_request_id(), None);
+}
@@ -0,0 +1,33 @@
+#[path = "../../stubs/prelude.rs"]
+mod stubs;
+use stubs::{Client, No, PhantomData, ToAssign, Yes};
+
+include!("../../snapshots/list_blobs_builder.rs");
+
+fn main() {
+    let client = Client;
+
+    // optional fields with a default are not wrapped in an `Option`
+    let builder = ListBlobsBuilder::new(&client);
+    assert_eq!(builder.timeout(), 30);
+    assert!(!builder.include_snapshots());
+    assert_eq!(builder.delimiter(), None);
+    assert_eq!(builder.marker(), None);
+
+    let builder = builder
+        .with_container_name("container")
+        .with_max_results(100)
+        .with_prefix("logs/".to_owned())
+        .with_timeout(10)
+        .with_include_snapshots(true)
+        .with_delimiter("/");
+    assert_eq!(builder.max_results(), 100);
+    assert_eq!(builder.prefix(), "logs/");
+    assert_eq!(builder.timeout(), 10);
+    assert!(builder.include_snapshots());
+    assert_eq!(builder.delimiter(), Some("/"));
+    assert_eq!(
+        builder.query(),
+        "container?prefix=logs/&maxresults=100&timeout=10&snapshots=true&delimiter=Some(\"/\")&marker=None"
+    );
+}
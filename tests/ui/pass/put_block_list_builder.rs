@@ -0,0 +1,25 @@
+#[path = "../../stubs/prelude.rs"]
+mod stubs;
+use stubs::*;
+
+include!("../../snapshots/put_block_list_builder.rs");
+
+fn main() {
+    let client = Client;
+    let block_list = BlockList {
+        blocks: vec![vec![0u8]],
+    };
+
+    let builder = PutBlockListBuilder::new(&client)
+        .with_timeout(30)
+        .with_container_name("container")
+        .with_block_list(&block_list)
+        .with_client_request_id("id");
+
+    assert_eq!(builder.container_name(), "container");
+    assert_eq!(builder.timeout(), Some(30));
+    assert_eq!(builder.client_request_id(), Some("id"));
+
+    // the finalizer has no body, it must be available but is not called
+    let _finalize = |builder: PutBlockListBuilder<Vec<u8>, Yes, Yes>| builder.finalize();
+}
@@ -0,0 +1,28 @@
+#[path = "../../stubs/prelude.rs"]
+mod stubs;
+use stubs::{Client, No, PhantomData, ToAssign, Yes};
+
+include!("../../snapshots/set_blob_metadata_builder.rs");
+
+fn main() {
+    let client = Client;
+
+    // the first item sets a required collection
+    let builder = SetBlobMetadataBuilder::new(&client)
+        .with_container_name("container")
+        .extend_tags(vec![("tier".to_owned(), "hot".to_owned())])
+        .add_metadata("owner".to_owned(), "me".to_owned())
+        .extend_metadata(vec![("team".to_owned(), "storage".to_owned())])
+        .add_snapshots("2019-01-01");
+
+    assert_eq!(builder.metadata().len(), 2);
+    assert_eq!(builder.tags().get("tier").map(String::as_str), Some("hot"));
+    assert_eq!(builder.snapshots(), &vec!["2019-01-01"]);
+    assert_eq!(builder.headers(), 4);
+
+    // or the whole collection at once
+    let builder = SetBlobMetadataBuilder::new(&client)
+        .with_metadata(HashMap::new())
+        .with_container_name("container");
+    assert_eq!(builder.headers(), 0);
+}
@@ -0,0 +1,29 @@
+#[path = "../../stubs/prelude.rs"]
+mod stubs;
+use stubs::{Client, ClientRequired, No, PhantomData, ToAssign, Yes};
+
+pub trait ContainerNameRequired {
+    fn container_name(&self) -> String;
+}
+
+pub trait ContainerNameSupport {
+    type O;
+    fn with_container_name<V: Into<String>>(self, container_name: V) -> Self::O;
+}
+
+include!("../../snapshots/setters.rs");
+
+fn main() {
+    let client = Client;
+    let blob_name = String::from("blob");
+
+    // a &str into the String, a &String as the &str and a u8 from a bool
+    let builder = PutBlobBuilder::new(&client)
+        .with_container_name("container")
+        .with_blob_name(&blob_name)
+        .with_number_of_things(true);
+
+    assert_eq!(builder.container_name(), "container");
+    assert_eq!(builder.blob_name(), "blob");
+    assert_eq!(builder.number_of_things(), Some(1));
+}
@@ -0,0 +1,19 @@
+// both builders import the prelude generated by `--emit-prelude` through
+// their `prelude_path`
+mod ts {
+    include!("../../snapshots/prelude.rs");
+}
+
+include!("../../snapshots/shared_prelude.rs");
+
+fn main() {
+    let queue = GetQueueBuilder::new()
+        .with_queue_name("queue")
+        .with_timeout(Duration::from_secs(30));
+    assert_eq!(queue.queue_name(), "queue");
+    assert_eq!(queue.timeout(), Some(Duration::from_secs(30)));
+
+    let message = PutMessageBuilder::new().with_message("message");
+    assert_eq!(message.message(), "message");
+    assert_eq!(message.visibility_timeout(), None);
+}
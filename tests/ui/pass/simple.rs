@@ -0,0 +1,21 @@
+#[path = "../../stubs/prelude.rs"]
+mod stubs;
+use stubs::{Client, ClientRequired, No, PhantomData, ToAssign, Yes};
+
+pub trait ContainerNameRequired {
+    fn container_name(&self) -> String;
+}
+
+pub trait ContainerNameSupport {
+    type O;
+    fn with_container_name(self, container_name: String) -> Self::O;
+}
+
+include!("../../snapshots/simple.rs");
+
+fn main() {
+    let client = Client;
+
+    let builder = SaratogaBuilder::new(&client).with_container_name("container".to_owned());
+    assert_eq!(builder.container_name(), "container");
+}
@@ -0,0 +1,38 @@
+#[path = "../../stubs/prelude.rs"]
+mod stubs;
+use stubs::*;
+
+pub trait BlobPathRequired<P> {
+    fn blob_path(&self) -> P;
+}
+
+pub trait BlobPathSupport<P> {
+    type O;
+    fn with_blob_path(self, blob_path: P) -> Self::O;
+}
+
+pub trait NumberOfThingsRequired {
+    fn number_of_things(&self) -> u8;
+}
+
+pub trait NumberOfThingsSupport {
+    type O;
+    fn with_number_of_things(self, number_of_things: u8) -> Self::O;
+}
+
+include!("../../snapshots/test.rs");
+
+fn main() {
+    let client = Client;
+    let constro = 3;
+
+    let builder = SaratogaBuilder::new(&client, &constro)
+        .with_number_of_things(7)
+        .with_blob_path(BlobPath("container", "blob"))
+        .with_optional_no_trait("value");
+
+    assert_eq!(builder.number_of_things(), 7);
+    assert_eq!(builder.blob_path().blob_name(), "blob");
+    assert_eq!(builder.optional_no_trait(), Some("value"));
+    assert_eq!(*builder.constro(), 3);
+}
@@ -0,0 +1,32 @@
+#[path = "../../stubs/prelude.rs"]
+mod stubs;
+use stubs::*;
+
+// the builder is `pub(crate)` and its getters and setters are callable
+// outside the module of the builder, except for the private ones
+mod builder {
+    use super::*;
+
+    include!("../../snapshots/visibility.rs");
+
+    pub(crate) fn with_timeout<'a>(builder: GetBlobBuilder<'a, No>) -> GetBlobBuilder<'a, No> {
+        builder.with_timeout(60)
+    }
+}
+
+use builder::GetBlobBuilder;
+
+fn main() {
+    let client = Client;
+
+    let builder = GetBlobBuilder::new(&client, 7).with_blob_name("blob");
+    assert_eq!(builder.snapshot(), 7);
+    assert_eq!(builder.blob_name(), "blob");
+    assert_eq!(builder.finalize(), 30);
+
+    let builder = builder::with_timeout(GetBlobBuilder::new(&client, 7));
+    assert_eq!(builder.with_blob_name("blob").finalize(), 60);
+
+    let builder = GetBlobBuilder::new(&client, 7).with_blob_name("blob");
+    assert_eq!(builder.into_blob_name(), "blob");
+}
@@ -0,0 +1,98 @@
+//! Unknown keys are rejected with the closest known key, unless the file is
+//! loaded leniently.
+
+extern crate bpb;
+
+mod common;
+
+use common::{dir, messages};
+use std::fs;
+
+const TYPO: &str = r#"{
+    "name": "SaratogaBuilder",
+    "fields": [
+        {
+            "name": "timeout",
+            "field_type": "u64",
+            "builder_type": "TimeoutSet",
+            "optinal": false,
+            "colour": "blue"
+        }
+    ]
+}"#;
+
+#[test]
+fn typos_are_reported_with_a_suggestion() {
+    assert_eq!(
+        messages(bpb::from_str(TYPO).unwrap_err()),
+        vec![
+            "8:13: fields[0].optinal: unknown key `optinal`, did you mean `optional`?",
+            "9:13: fields[0].colour: unknown key `colour`",
+        ]
+    );
+}
+
+#[test]
+fn every_syntax_and_document_is_checked() {
+    let text = "name: SaratogaBuilder\ntypestate:\n  sealed: true\n  unsett: Unset\nfields: []\n";
+    assert_eq!(
+        messages(bpb::from_str_syntax(text, bpb::Syntax::Yaml).unwrap_err()),
+        vec!["4:3: typestate.unsett: unknown key `unsett`, did you mean `unset`?"]
+    );
+
+    let catalog = r#"{ "fields": { "timeout": { "name": "timeout", "field_typ": "u64" } } }"#;
+    assert_eq!(
+        messages(bpb::catalog_from_str(catalog).unwrap_err()),
+        vec!["1:47: fields.timeout.field_typ: unknown key `field_typ`, did you mean `field_type`?"]
+    );
+}
+
+#[test]
+fn unknown_keys_are_located_in_every_syntax() {
+    let texts = [
+        (
+            bpb::Syntax::Json5,
+            "// a misspelled key\n{\n  name: 'SaratogaBuilder',\n  fields: [{ name: 'timeout', field_type: 'u64', optional: true, optinal: true }],\n}\n",
+        ),
+        (
+            bpb::Syntax::Toml,
+            "name = \"SaratogaBuilder\"\n\n[[fields]]\nname = \"timeout\"\nfield_type = \"u64\"\noptional = true\noptinal = true\n",
+        ),
+        (
+            bpb::Syntax::Yaml,
+            "name: SaratogaBuilder\nfields:\n  - name: timeout\n    field_type: u64\n    optional: true\n    optinal: true\n",
+        ),
+        (
+            bpb::Syntax::Ron,
+            "(\n    name: \"SaratogaBuilder\",\n    fields: [(name: \"timeout\", field_type: \"u64\", optional: true, optinal: true)],\n)\n",
+        ),
+    ];
+
+    for &(syntax, text) in texts.iter() {
+        let (line, typo) = text
+            .lines()
+            .enumerate()
+            .find(|&(_, l)| l.contains("optinal"))
+            .unwrap();
+        assert_eq!(
+            messages(bpb::from_str_syntax(text, syntax).unwrap_err()),
+            vec![format!(
+                "{}:{}: fields[0].optinal: unknown key `optinal`, did you mean `optional`?",
+                line + 1,
+                typo.find("optinal").unwrap() + 1
+            )],
+            "{:?}",
+            syntax
+        );
+    }
+}
+
+#[test]
+fn lenient_loading_ignores_unknown_keys() {
+    let path = dir("lenient_loading_ignores_unknown_keys").join("builder.json");
+    fs::write(&path, TYPO).unwrap();
+
+    assert!(bpb::load_all(&path).is_err());
+    let structs = bpb::load_all_lenient(&path, None).unwrap();
+    assert_eq!(structs[0].fields[0].name, "timeout");
+}
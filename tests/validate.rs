@@ -0,0 +1,107 @@
+//! Reports every problem of an invalid definition with the file, line and
+//! column it is found at, through the library and the command line, and
+//! rejects names that would clash in the generated code.
+
+extern crate bpb;
+
+mod common;
+
+use common::messages;
+use std::path::PathBuf;
+use std::process::Command;
+
+// the problems of `tests/fixtures/invalid.json`
+const EXPECTED: &[&str] = &[
+    "2:2: name: the builder name cannot be empty",
+    "4:3: fields[0]: required field `container_name` has no `builder_type`",
+    "13:4: fields[1].trait_set: field `timeout` has a `trait_set` but no `trait_get`",
+    "16:4: fields[2].name: field name `container_name` is already used by fields[0].name",
+];
+
+fn fixture() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/invalid.json")
+}
+
+#[test]
+fn problems_are_reported_with_their_file_and_location() {
+    let path = fixture();
+    let expected: Vec<String> = EXPECTED
+        .iter()
+        .map(|e| format!("{}:{}", path.display(), e))
+        .collect();
+
+    let message = bpb::load(&path).unwrap_err().in_file(&path).to_string();
+    assert_eq!(message.lines().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn the_command_line_prints_the_problems_and_fails() {
+    let output = Command::new(env!("CARGO_BIN_EXE_bpb"))
+        .arg(fixture())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    for e in EXPECTED {
+        assert!(
+            stderr.contains(&format!("invalid.json:{}\n", e)),
+            "{} not in\n{}",
+            e,
+            stderr
+        );
+    }
+}
+
+#[test]
+fn builder_types_cannot_be_extra_types_or_markers() {
+    let text = r#"{
+    "name": "GetBlobBuilder",
+    "extra_types": ["'a", "T"],
+    "typestate": { "set": "Assigned", "unset": "Unassigned" },
+    "fields": [
+        { "name": "container_name", "field_type": "&'a str", "builder_type": "T" },
+        { "name": "blob_name", "field_type": "&'a str", "builder_type": "Assigned" },
+        { "name": "lease_id", "field_type": "&'a str", "builder_type": "Unassigned" }
+    ]
+}"#;
+    assert_eq!(
+        messages(bpb::from_str(text).unwrap_err()),
+        vec![
+            "6:62: fields[0].builder_type: builder type `T` is already an extra type",
+            "7:57: fields[1].builder_type: builder type `Assigned` is also a typestate marker",
+            "8:56: fields[2].builder_type: builder type `Unassigned` is also a typestate marker",
+        ]
+    );
+}
+
+#[test]
+fn finalizers_cannot_be_named_after_generated_methods() {
+    let text = r#"{
+    "name": "GetBlobBuilder",
+    "constructor_fields": [{ "name": "client", "field_type": "u64" }],
+    "fields": [
+        { "name": "timeout", "field_type": "u64", "optional": true },
+        { "name": "tags", "field_type": "Vec<String>", "optional": true, "collection": true }
+    ],
+    "finalizers": [
+        { "name": "new" },
+        { "name": "client" },
+        { "name": "timeout" },
+        { "name": "with_timeout" },
+        { "name": "extend_tags" },
+        { "name": "finalize" }
+    ]
+}"#;
+    assert_eq!(
+        messages(bpb::from_str(text).unwrap_err()),
+        vec![
+            "9:11: finalizers[0].name: finalizer `new` has the same name as the constructor",
+            "10:11: finalizers[1].name: finalizer `client` has the same name as the getter of constructor field `client`",
+            "11:11: finalizers[2].name: finalizer `timeout` has the same name as the getter of field `timeout`",
+            "12:11: finalizers[3].name: finalizer `with_timeout` has the same name as the setter of field `timeout`",
+            "13:11: finalizers[4].name: finalizer `extend_tags` has the same name as the setter of field `tags`",
+        ]
+    );
+}